@@ -1,5 +1,12 @@
+#[cfg(windows)]
 use aojia::*;
 
+#[cfg(not(windows))]
+fn main() {
+    println!("奥加插件只能在 Windows 下使用");
+}
+
+#[cfg(windows)]
 fn main() {
     let aojia = AoJia::new_with_path(String::from("ARegJ64.dll"), String::from("AoJia64.dll")).unwrap();
     println!("插件版本：{}", aojia.VerS().unwrap());
@@ -22,4 +29,3 @@ fn main() {
     println!("GetCPU ret: {}, ty: {}, id: {}", ret, ty, id);
 
 }
-
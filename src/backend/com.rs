@@ -0,0 +1,274 @@
+use once_cell::sync::OnceCell;
+use std::ptr;
+use windows::{
+    Win32::{
+        Globalization::GetUserDefaultLCID,
+        System::{
+            Com::{
+                CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+                CoUninitialize, DISPATCH_METHOD, DISPPARAMS, IDispatch,
+            },
+            LibraryLoader::{GetProcAddress, LoadLibraryW},
+            Variant::{
+                VAR_CHANGE_FLAGS, VARENUM, VARIANT, VARIANT_0_0, VT_BOOL, VT_BSTR, VT_BYREF,
+                VT_EMPTY, VT_I1, VT_I2, VT_I4, VT_I8, VT_INT, VT_R4, VT_R8, VT_UI1, VT_UI2, VT_UI4,
+                VT_UI8, VT_UINT, VT_VARIANT, VariantChangeType, VariantClear,
+            },
+        },
+    },
+    core::{GUID, HSTRING, PCWSTR, s},
+};
+
+use std::mem::ManuallyDrop;
+
+use super::{Arg, Backend, Value};
+
+pub trait VariantExt {
+    fn by_ref(var_val: *mut VARIANT) -> VARIANT;
+    fn to_i32(&self) -> windows::core::Result<i32>;
+    fn to_i64(&self) -> windows::core::Result<i64>;
+    fn to_f64(&self) -> windows::core::Result<f64>;
+    fn to_string(&self) -> windows::core::Result<String>;
+    fn to_bool(&self) -> windows::core::Result<bool>;
+}
+
+impl VariantExt for VARIANT {
+    fn by_ref(var_val: *mut VARIANT) -> VARIANT {
+        let mut variant = VARIANT::default();
+        let mut v00 = VARIANT_0_0 {
+            vt: VARENUM(VT_BYREF.0 | VT_VARIANT.0),
+            ..Default::default()
+        };
+        v00.Anonymous.pvarVal = var_val;
+        variant.Anonymous.Anonymous = ManuallyDrop::new(v00);
+        variant
+    }
+    fn to_i32(&self) -> windows::core::Result<i32> {
+        unsafe {
+            let mut new = VARIANT::default();
+            VariantChangeType(&mut new, self, VAR_CHANGE_FLAGS(0), VT_I4)?;
+            let v00 = &new.Anonymous.Anonymous;
+            let n = v00.Anonymous.lVal;
+            VariantClear(&mut new)?;
+            Ok(n)
+        }
+    }
+    fn to_i64(&self) -> windows::core::Result<i64> {
+        unsafe {
+            let mut new = VARIANT::default();
+            VariantChangeType(&mut new, self, VAR_CHANGE_FLAGS(0), VT_I8)?;
+            let v00 = &new.Anonymous.Anonymous;
+            let n = v00.Anonymous.llVal;
+            VariantClear(&mut new)?;
+            Ok(n)
+        }
+    }
+    fn to_f64(&self) -> windows::core::Result<f64> {
+        unsafe {
+            let mut new = VARIANT::default();
+            VariantChangeType(&mut new, self, VAR_CHANGE_FLAGS(0), VT_R8)?;
+            let v00 = &new.Anonymous.Anonymous;
+            let n = v00.Anonymous.dblVal;
+            VariantClear(&mut new)?;
+            Ok(n)
+        }
+    }
+    fn to_string(&self) -> windows::core::Result<String> {
+        unsafe {
+            let mut new = VARIANT::default();
+            VariantChangeType(&mut new, self, VAR_CHANGE_FLAGS(0), VT_BSTR)?;
+            let v00 = &new.Anonymous.Anonymous;
+            let str = v00.Anonymous.bstrVal.to_string();
+            VariantClear(&mut new)?;
+            Ok(str)
+        }
+    }
+    fn to_bool(&self) -> windows::core::Result<bool> {
+        unsafe {
+            let mut new = VARIANT::default();
+            VariantChangeType(&mut new, self, VAR_CHANGE_FLAGS(0), VT_BOOL)?;
+            let v00 = &new.Anonymous.Anonymous;
+            let b = v00.Anonymous.boolVal.as_bool();
+            VariantClear(&mut new)?;
+            Ok(b)
+        }
+    }
+}
+
+impl From<&Value> for VARIANT {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Empty => VARIANT::default(),
+            Value::I32(n) => VARIANT::from(*n),
+            Value::I64(n) => VARIANT::from(*n),
+            Value::F64(n) => VARIANT::from(*n),
+            Value::Str(s) => VARIANT::from(s.as_str()),
+        }
+    }
+}
+
+impl From<&VARIANT> for Value {
+    fn from(var: &VARIANT) -> Self {
+        let vt = var.vt();
+        let value = if vt == VT_EMPTY {
+            return Value::Empty;
+        } else if [VT_I1, VT_I2, VT_I4, VT_INT, VT_UI1, VT_UI2, VT_BOOL].contains(&vt) {
+            var.to_i32().map(Value::I32)
+        } else if [VT_I8, VT_UI4, VT_UI8, VT_UINT].contains(&vt) {
+            var.to_i64().map(Value::I64)
+        } else if [VT_R4, VT_R8].contains(&vt) {
+            var.to_f64().map(Value::F64)
+        } else {
+            VariantExt::to_string(var).map(Value::Str)
+        };
+        value.unwrap_or_default()
+    }
+}
+
+// 对应 CARegJ 类
+type FnSetDllPathW = unsafe extern "system" fn(PCWSTR, i32) -> i32;
+static PFN_SET_DLL_PATH_W: OnceCell<Option<FnSetDllPathW>> = OnceCell::new();
+
+fn set_dll_path(a_regj_path: String, ao_jia_path: String) -> i32 {
+    let pfn = PFN_SET_DLL_PATH_W.get_or_init(|| unsafe {
+        let a_regj_hstring = HSTRING::from(a_regj_path);
+        let hmodule = LoadLibraryW(PCWSTR::from_raw(a_regj_hstring.as_ptr())).ok();
+        hmodule.and_then(|h| {
+            GetProcAddress(h, s!("SetDllPathW")).map(|addr| std::mem::transmute(addr))
+        })
+    });
+
+    if let Some(func) = pfn {
+        unsafe {
+            let ao_jia_hstring = HSTRING::from(ao_jia_path);
+            func(PCWSTR::from_raw(ao_jia_hstring.as_ptr()), 0)
+        }
+    } else {
+        0
+    }
+}
+
+/// 通过 IDispatch 调用奥加插件
+#[derive(Debug)]
+pub struct ComBackend {
+    p_idispatch: Option<IDispatch>,
+}
+
+impl ComBackend {
+    const CLSID: GUID = GUID::from_values(
+        0x4f27e588,
+        0x5b1e,
+        0x45b4,
+        [0xad, 0x67, 0xe3, 0x2d, 0x45, 0xc4, 0xe9, 0xca],
+    );
+
+    pub fn new() -> windows::core::Result<Self> {
+        unsafe {
+            let hr = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            if hr.is_err() {
+                return Err(hr.into());
+            }
+
+            let idispatch: IDispatch = CoCreateInstance(&Self::CLSID, None, CLSCTX_INPROC_SERVER)?;
+
+            Ok(Self {
+                p_idispatch: Some(idispatch),
+            })
+        }
+    }
+
+    pub fn new_with_path(a_regj_path: String, ao_jia_path: String) -> windows::core::Result<Self> {
+        set_dll_path(a_regj_path, ao_jia_path);
+        Self::new()
+    }
+
+    fn dispatch(
+        &self,
+        fun_name: &HSTRING,
+        rgdispid: &mut i32,
+        p_disp_params: &DISPPARAMS,
+        p_var_result: &mut VARIANT,
+    ) -> windows::core::Result<()> {
+        unsafe {
+            if *rgdispid == -1 {
+                let names_ptr = PCWSTR::from_raw(fun_name.as_ptr());
+                let names = [names_ptr];
+                self.p_idispatch.as_ref().unwrap().GetIDsOfNames(
+                    &GUID::default(),
+                    names.as_ptr(),
+                    1,
+                    GetUserDefaultLCID(),
+                    rgdispid,
+                )?;
+            }
+
+            self.p_idispatch.as_ref().unwrap().Invoke(
+                *rgdispid,
+                &GUID::default(),
+                GetUserDefaultLCID(),
+                DISPATCH_METHOD,
+                p_disp_params,
+                Some(p_var_result),
+                None,
+                None,
+            )
+        }
+    }
+}
+
+impl Backend for ComBackend {
+    fn invoke(&self, fun_name: &str, args: &mut [Arg<'_>]) -> crate::Result<Value> {
+        let fun_name = HSTRING::from(fun_name);
+        let mut disp_id = -1;
+        let mut var_result = VARIANT::default();
+
+        // 创建返回值的变量，invoke 期间地址不能变化，传入时带上原有的值
+        let mut outs: Vec<VARIANT> = args
+            .iter()
+            .map(|arg| match arg {
+                Arg::In(_) => VARIANT::default(),
+                Arg::Out(value) => VARIANT::from(&**value),
+            })
+            .collect();
+        let mut rgvarg: Vec<VARIANT> = args
+            .iter()
+            .zip(outs.iter_mut())
+            .map(|(arg, out)| match arg {
+                Arg::In(value) => VARIANT::from(value),
+                Arg::Out(_) => VARIANT::by_ref(out as *mut VARIANT),
+            })
+            .collect();
+
+        let disp_params = DISPPARAMS {
+            rgvarg: if rgvarg.is_empty() {
+                ptr::null_mut()
+            } else {
+                rgvarg.as_mut_ptr()
+            },
+            rgdispidNamedArgs: ptr::null_mut(),
+            cArgs: rgvarg.len() as u32,
+            cNamedArgs: 0,
+        };
+
+        self.dispatch(&fun_name, &mut disp_id, &disp_params, &mut var_result)?;
+
+        // 获取返回值
+        for (arg, out) in args.iter_mut().zip(&outs) {
+            if let Arg::Out(value) = arg {
+                **value = Value::from(out);
+            }
+        }
+        Ok(Value::from(&var_result))
+    }
+}
+
+impl Drop for ComBackend {
+    fn drop(&mut self) {
+        unsafe {
+            // IDispatch implements Drop which will call Release internally
+            // Just let it drop automatically
+            self.p_idispatch.take();
+            CoUninitialize();
+        }
+    }
+}
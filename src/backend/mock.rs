@@ -0,0 +1,215 @@
+//! 不依赖插件的模拟后端，记录每次调用，并可按函数名设置返回值。
+//! 未设置处理函数时 FindPic 和 FindPicEx 使用 [`Matcher`] 在 [`MockBackend::set_screen`] 设置的截图上找图。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::{Arg, Backend, Value};
+use crate::pic::{Bitmap, Matcher, PicMatch, matcher};
+use crate::{Error, Result};
+
+/// 记录下来的一个参数，`Out` 为按引用传递的参数及其传入时的值
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedArg {
+    In(Value),
+    Out(Value),
+}
+
+/// 一次插件函数调用，参数按插件文档中的顺序排列
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub name: String,
+    pub args: Vec<RecordedArg>,
+}
+
+type Handler = Box<dyn FnMut(&mut [Arg<'_>]) -> Result<Value> + Send>;
+
+#[derive(Default)]
+struct State {
+    calls: Vec<Call>,
+    handlers: HashMap<String, Handler>,
+    path: PathBuf,
+    screen: Option<Bitmap>,
+    pics: HashMap<String, Bitmap>,
+}
+
+/// 克隆得到的句柄共享同一份状态，可以交给 [`AoJia`](crate::AoJia) 后继续设置和检查
+#[derive(Clone, Default)]
+pub struct MockBackend {
+    state: Arc<Mutex<State>>,
+}
+
+impl fmt::Debug for MockBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockBackend")
+            .field("calls", &self.state().calls.len())
+            .finish()
+    }
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 设置函数的处理函数，参数按插件文档中的顺序传入，`Arg::Out` 可直接写入返回参数
+    pub fn on<F>(&self, fun_name: &str, handler: F)
+    where
+        F: FnMut(&mut [Arg<'_>]) -> Result<Value> + Send + 'static,
+    {
+        self.state()
+            .handlers
+            .insert(fun_name.to_string(), Box::new(handler));
+    }
+
+    /// 让这些函数都返回 1，即插件表示成功的返回值
+    pub fn succeed(&self, fun_names: &[&str]) {
+        for name in fun_names {
+            self.on(name, |_| Ok(Value::I32(1)));
+        }
+    }
+
+    /// 新建模拟后端并对这些函数调用 [`succeed`](Self::succeed)
+    pub fn succeeding(fun_names: &[&str]) -> Self {
+        let mock = Self::new();
+        mock.succeed(fun_names);
+        mock
+    }
+
+    /// 设置 FindPic 查找时使用的屏幕截图
+    pub fn set_screen(&self, screen: Bitmap) {
+        self.state().screen = Some(screen);
+    }
+
+    /// 添加内存中的图片，FindPic 优先使用它，找不到时再到 SetPath 设置的目录中加载
+    pub fn add_pic(&self, name: &str, pic: Bitmap) {
+        self.state().pics.insert(name.to_string(), pic);
+    }
+
+    pub fn calls(&self) -> Vec<Call> {
+        self.state().calls.clone()
+    }
+
+    /// 按调用顺序排列的函数名
+    pub fn call_names(&self) -> Vec<String> {
+        self.state().calls.iter().map(|c| c.name.clone()).collect()
+    }
+
+    pub fn clear_calls(&self) {
+        self.state().calls.clear();
+    }
+
+    /// 按 FindPic 和 FindPicEx 共有的前 9 个参数找图
+    fn search(&self, args: &[Arg<'_>], all: bool) -> Result<Vec<PicMatch>> {
+        let input = |i: usize| match args.get(i) {
+            Some(Arg::In(value)) => Ok(value.clone()),
+            _ => Err(Error::InvalidArgument(format!("找图第 {} 个参数", i + 1))),
+        };
+        let region = (
+            input(0)?.to_i32()?,
+            input(1)?.to_i32()?,
+            input(2)?.to_i32()?,
+            input(3)?.to_i32()?,
+        );
+        let pic_name = input(4)?.to_string()?;
+        let matcher = Matcher::new(
+            &input(5)?.to_string()?,
+            input(6)?.to_f64()?.try_into()?,
+            input(7)?.to_i32()?.try_into()?,
+            input(8)?.to_i32()?.try_into()?,
+        )?;
+
+        let state = self.state();
+        let screen = state
+            .screen
+            .as_ref()
+            .ok_or_else(|| Error::Unsupported("模拟后端未设置屏幕截图".to_string()))?;
+        let mut pics = Vec::new();
+        for name in pic_name.split('|').filter(|name| !name.is_empty()) {
+            match state.pics.get(name) {
+                Some(pic) => pics.push((name.to_string(), pic.clone())),
+                None => pics.extend(matcher::load_pics(&state.path, name)?),
+            }
+        }
+        let pics: Vec<(&str, &Bitmap)> = pics.iter().map(|(n, p)| (n.as_str(), p)).collect();
+        if all {
+            Ok(matcher.find_all(screen, region, &pics))
+        } else {
+            Ok(matcher.find(screen, region, &pics).into_iter().collect())
+        }
+    }
+
+    fn find_pic(&self, args: &mut [Arg<'_>]) -> Result<Value> {
+        let found = self.search(args, false)?.pop();
+        let (ret, pic, x, y) = match found {
+            Some(m) => (m.index as i32, m.name, m.x, m.y),
+            None => (-1, String::new(), -1, -1),
+        };
+        for (i, value) in [(9, Value::from(pic)), (10, x.into()), (11, y.into())] {
+            if let Some(Arg::Out(out)) = args.get_mut(i) {
+                **out = value;
+            }
+        }
+        Ok(ret.into())
+    }
+
+    fn call(&self, fun_name: &str, args: &mut [Arg<'_>]) -> Result<Value> {
+        let handler = self.state().handlers.remove(fun_name);
+        if let Some(mut handler) = handler {
+            let ret = handler(args);
+            // 处理函数执行期间可能设置了新的处理函数，此时不再放回
+            self.state()
+                .handlers
+                .entry(fun_name.to_string())
+                .or_insert(handler);
+            return ret;
+        }
+
+        match fun_name {
+            "SetPath" => {
+                if let Some(Arg::In(path)) = args.first() {
+                    self.state().path = PathBuf::from(path.to_string()?);
+                }
+                Ok(1.into())
+            }
+            "GetPath" => Ok(self.state().path.to_string_lossy().into_owned().into()),
+            "FindPic" => self.find_pic(args),
+            "FindPicEx" => {
+                let found = self.search(args, true)?;
+                let ret: Vec<String> = found
+                    .iter()
+                    .map(|m| format!("{},{},{}", m.index, m.x, m.y))
+                    .collect();
+                Ok(ret.join("|").into())
+            }
+            _ => Ok(Value::Empty),
+        }
+    }
+}
+
+impl Backend for MockBackend {
+    fn invoke(&self, fun_name: &str, args: &mut [Arg<'_>]) -> Result<Value> {
+        // 转回插件文档中的参数顺序，返回前再恢复
+        args.reverse();
+        let call = Call {
+            name: fun_name.to_string(),
+            args: args
+                .iter()
+                .map(|arg| match arg {
+                    Arg::In(value) => RecordedArg::In(value.clone()),
+                    Arg::Out(value) => RecordedArg::Out((**value).clone()),
+                })
+                .collect(),
+        };
+        self.state().calls.push(call);
+        let ret = self.call(fun_name, args);
+        args.reverse();
+        ret
+    }
+}
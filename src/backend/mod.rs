@@ -0,0 +1,141 @@
+#[cfg(windows)]
+pub mod com;
+pub mod mock;
+
+use std::fmt;
+
+use crate::{Error, Result};
+
+#[cfg(windows)]
+pub use com::ComBackend;
+pub use mock::MockBackend;
+
+/// 插件函数的参数或返回值，对应 COM 的 VARIANT
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Value {
+    #[default]
+    Empty,
+    I32(i32),
+    I64(i64),
+    F64(f64),
+    Str(String),
+}
+
+impl Value {
+    fn mismatch(&self, to: &str) -> Error {
+        Error::Convert(format!("{:?} -> {}", self, to))
+    }
+
+    pub fn to_i32(&self) -> Result<i32> {
+        match self {
+            Value::Empty => Ok(0),
+            Value::I32(n) => Ok(*n),
+            Value::I64(n) => i32::try_from(*n).map_err(|_| self.mismatch("i32")),
+            Value::F64(n) => Ok(n.round() as i32),
+            Value::Str(s) => s.trim().parse().map_err(|_| self.mismatch("i32")),
+        }
+    }
+
+    pub fn to_i64(&self) -> Result<i64> {
+        match self {
+            Value::Empty => Ok(0),
+            Value::I32(n) => Ok(*n as i64),
+            Value::I64(n) => Ok(*n),
+            Value::F64(n) => Ok(n.round() as i64),
+            Value::Str(s) => s.trim().parse().map_err(|_| self.mismatch("i64")),
+        }
+    }
+
+    pub fn to_f64(&self) -> Result<f64> {
+        match self {
+            Value::Empty => Ok(0.0),
+            Value::I32(n) => Ok(*n as f64),
+            Value::I64(n) => Ok(*n as f64),
+            Value::F64(n) => Ok(*n),
+            Value::Str(s) => s.trim().parse().map_err(|_| self.mismatch("f64")),
+        }
+    }
+
+    pub fn to_string(&self) -> Result<String> {
+        match self {
+            Value::Empty => Ok(String::new()),
+            Value::I32(n) => Ok(n.to_string()),
+            Value::I64(n) => Ok(n.to_string()),
+            Value::F64(n) => Ok(n.to_string()),
+            Value::Str(s) => Ok(s.clone()),
+        }
+    }
+}
+
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Value::I32(n)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::I64(n)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::F64(n)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Str(s)
+    }
+}
+
+/// 插件函数的一个参数，`Out` 对应按引用传递的参数，调用前的值会一并传入
+#[derive(Debug)]
+pub enum Arg<'a> {
+    In(Value),
+    Out(&'a mut Value),
+}
+
+impl From<i32> for Arg<'_> {
+    fn from(n: i32) -> Self {
+        Arg::In(n.into())
+    }
+}
+
+impl From<i64> for Arg<'_> {
+    fn from(n: i64) -> Self {
+        Arg::In(n.into())
+    }
+}
+
+impl From<f64> for Arg<'_> {
+    fn from(n: f64) -> Self {
+        Arg::In(n.into())
+    }
+}
+
+impl From<&str> for Arg<'_> {
+    fn from(s: &str) -> Self {
+        Arg::In(s.into())
+    }
+}
+
+impl<'a> From<&'a mut Value> for Arg<'a> {
+    fn from(v: &'a mut Value) -> Self {
+        Arg::Out(v)
+    }
+}
+
+/// 插件函数的调用方式，默认为 COM，测试时可替换为 [`MockBackend`]
+pub trait Backend: fmt::Debug {
+    /// `args` 与 DISPPARAMS::rgvarg 一致，按照COM调用约定，参数顺序是反向的
+    fn invoke(&self, fun_name: &str, args: &mut [Arg<'_>]) -> Result<Value>;
+}
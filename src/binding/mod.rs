@@ -0,0 +1,164 @@
+//! KQHouTai 和 GBHouTai 的绑定守卫。
+//!
+//! 一个 [`AoJia`] 同时只能绑定一个窗口，[`BindingGuard`] drop 时自动解绑，
+//! 提前返回或 panic 都不会让插件留在已失效的窗口上。
+
+mod mode;
+mod supervisor;
+
+use std::cell::RefCell;
+
+use crate::{AoJia, Error, Hwnd, Result, error};
+
+pub use mode::{BindFlags, KeyboardMode, MouseMode, ScreenMode};
+pub use supervisor::{BindingEvent, BindingSupervisor, RebindReason, SupervisorOptions};
+
+/// KQHouTai 的 Screen、Keyboard、Mouse、Flag 和 Type 参数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BindMode {
+    pub screen: ScreenMode,
+    pub keyboard: KeyboardMode,
+    pub mouse: MouseMode,
+    pub flags: BindFlags,
+    pub ty: i32,
+}
+
+impl BindMode {
+    pub fn new(screen: ScreenMode, keyboard: KeyboardMode, mouse: MouseMode) -> Self {
+        Self {
+            screen,
+            keyboard,
+            mouse,
+            ..Default::default()
+        }
+    }
+
+    /// 从插件字符串解析，并检查组合是否合法
+    pub fn parse(screen: &str, keyboard: &str, mouse: &str, flag: &str, ty: i32) -> Result<Self> {
+        let mode = Self {
+            screen: screen.parse()?,
+            keyboard: keyboard.parse()?,
+            mouse: mouse.parse()?,
+            flags: flag.parse()?,
+            ty,
+        };
+        mode.validate()?;
+        Ok(mode)
+    }
+
+    pub fn flags(mut self, flags: BindFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn ty(mut self, ty: i32) -> Self {
+        self.ty = ty;
+        self
+    }
+
+    /// 检查 Flag 与各模式的组合
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: &str| Err(Error::InvalidArgument(format!("{}: {:?}", msg, self)));
+        if self
+            .flags
+            .contains(BindFlags::ACTIVE_API | BindFlags::ACTIVE_MESSAGE)
+        {
+            return invalid("dx.public.active.api 和 dx.public.active.message 不能同时使用");
+        }
+        let any_dx = self.screen == ScreenMode::Dx
+            || self.screen == ScreenMode::Dx2
+            || self.keyboard == KeyboardMode::Dx
+            || self.mouse == MouseMode::Dx;
+        if !self.flags.is_empty() && !any_dx {
+            return invalid("Flag 需要至少一种 dx 模式");
+        }
+        let mouse_locks = BindFlags::LOCK_MOUSE_POSITION | BindFlags::LOCK_MOUSE_CLIP;
+        if self.flags.iter().any(|f| mouse_locks.contains(f)) && self.mouse != MouseMode::Dx {
+            return invalid("锁定鼠标需要 dx 鼠标模式");
+        }
+        if self.flags.contains(BindFlags::LOCK_KEYBOARD) && self.keyboard != KeyboardMode::Dx {
+            return invalid("屏蔽键盘需要 dx 键盘模式");
+        }
+        Ok(())
+    }
+}
+
+/// 当前绑定的窗口和模式
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Binding {
+    pub hwnd: Hwnd,
+    pub mode: BindMode,
+}
+
+/// [`AoJia`] 中记录的绑定状态
+#[derive(Debug, Default)]
+pub(crate) struct BindingState(RefCell<Option<Binding>>);
+
+/// 绑定期间持有，drop 时调用 GBHouTai 解绑
+#[derive(Debug)]
+#[must_use = "守卫 drop 时会立即解绑"]
+pub struct BindingGuard<'a> {
+    aojia: &'a AoJia,
+    binding: Binding,
+    released: bool,
+}
+
+impl BindingGuard<'_> {
+    pub fn hwnd(&self) -> Hwnd {
+        self.binding.hwnd
+    }
+
+    pub fn mode(&self) -> &BindMode {
+        &self.binding.mode
+    }
+
+    /// 立即解绑并返回 GBHouTai 的结果
+    pub fn unbind(mut self) -> Result<()> {
+        self.released = true;
+        self.aojia.release_binding()
+    }
+}
+
+impl Drop for BindingGuard<'_> {
+    fn drop(&mut self) {
+        if !self.released {
+            let _ = self.aojia.release_binding();
+        }
+    }
+}
+
+impl AoJia {
+    /// 检查模式后调用 KQHouTai 绑定窗口，已有绑定时返回 [`Error::AlreadyBound`]
+    pub fn bind(&self, hwnd: Hwnd, mode: BindMode) -> Result<BindingGuard<'_>> {
+        if let Some(binding) = self.binding() {
+            return Err(Error::AlreadyBound(binding.hwnd));
+        }
+        mode.validate()?;
+        let ret = self.KQHouTai(
+            hwnd,
+            mode.screen.as_str(),
+            mode.keyboard.as_str(),
+            mode.mouse.as_str(),
+            &mode.flags.to_string(),
+            mode.ty,
+        )?;
+        error::check("KQHouTai", ret)?;
+        let binding = Binding { hwnd, mode };
+        *self.binding.0.borrow_mut() = Some(binding.clone());
+        Ok(BindingGuard {
+            aojia: self,
+            binding,
+            released: false,
+        })
+    }
+
+    /// 当前绑定的窗口和模式，没有绑定时返回 None
+    pub fn binding(&self) -> Option<Binding> {
+        self.binding.0.borrow().clone()
+    }
+
+    fn release_binding(&self) -> Result<()> {
+        self.binding.0.borrow_mut().take();
+        error::check("GBHouTai", self.GBHouTai()?)
+    }
+}
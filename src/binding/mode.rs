@@ -0,0 +1,180 @@
+//! KQHouTai 的 Screen、Keyboard、Mouse 和 Flag 参数。
+//!
+//! 每种模式都可以用 `to_string` 得到插件使用的字符串，用 `parse` 解析回来。
+//! Flag 是以 `|` 连接的多个 `dx.*` 选项，只在相应的 dx 模式下才有意义，
+//! [`BindMode::validate`](super::BindMode::validate) 会检查这些组合。
+
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+use std::str::FromStr;
+
+use crate::{Error, Result};
+
+macro_rules! string_enum {
+    ($(#[$meta:meta])* $name:ident, $what:literal { $($(#[$vmeta:meta])* $variant:ident => $s:literal,)+ }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub enum $name {
+            #[default]
+            $($(#[$vmeta])* $variant,)+
+        }
+
+        impl $name {
+            pub const ALL: &'static [$name] = &[$($name::$variant,)+];
+
+            /// 插件使用的字符串
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $s,)+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self> {
+                match s.trim() {
+                    $($s => Ok($name::$variant),)+
+                    _ => Err(Error::InvalidArgument(format!(
+                        concat!($what, " \"{}\" 不存在"),
+                        s
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// 屏幕颜色的获取方式
+    ScreenMode, "Screen 模式" {
+        /// 前台
+        Normal => "normal",
+        Gdi => "gdi",
+        Gdi2 => "gdi2",
+        Dx => "dx",
+        Dx2 => "dx2",
+    }
+}
+
+string_enum! {
+    /// 键盘消息的发送方式
+    KeyboardMode, "Keyboard 模式" {
+        /// 前台
+        Normal => "normal",
+        Windows => "windows",
+        Dx => "dx",
+    }
+}
+
+string_enum! {
+    /// 鼠标消息的发送方式
+    MouseMode, "Mouse 模式" {
+        /// 前台
+        Normal => "normal",
+        Windows => "windows",
+        Windows2 => "windows2",
+        Dx => "dx",
+    }
+}
+
+/// Flag 参数，可以用 `|` 组合
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BindFlags(u32);
+
+impl BindFlags {
+    pub const NONE: BindFlags = BindFlags(0);
+    /// 通过 API 让窗口保持激活状态
+    pub const ACTIVE_API: BindFlags = BindFlags(1 << 0);
+    /// 通过消息让窗口保持激活状态
+    pub const ACTIVE_MESSAGE: BindFlags = BindFlags(1 << 1);
+    /// 隐藏注入的模块
+    pub const HIDE_DLL: BindFlags = BindFlags(1 << 2);
+    /// 支持输入法输入
+    pub const INPUT_IME: BindFlags = BindFlags(1 << 3);
+    /// 锁定鼠标位置
+    pub const LOCK_MOUSE_POSITION: BindFlags = BindFlags(1 << 4);
+    /// 锁定鼠标活动范围
+    pub const LOCK_MOUSE_CLIP: BindFlags = BindFlags(1 << 5);
+    /// 屏蔽前台键盘输入
+    pub const LOCK_KEYBOARD: BindFlags = BindFlags(1 << 6);
+
+    /// 每个选项和对应的插件字符串，按序列化顺序排列
+    pub const ALL: &'static [(BindFlags, &'static str)] = &[
+        (Self::ACTIVE_API, "dx.public.active.api"),
+        (Self::ACTIVE_MESSAGE, "dx.public.active.message"),
+        (Self::HIDE_DLL, "dx.public.hide.dll"),
+        (Self::INPUT_IME, "dx.public.input.ime"),
+        (Self::LOCK_MOUSE_POSITION, "dx.mouse.position.lock.api"),
+        (Self::LOCK_MOUSE_CLIP, "dx.mouse.clip.lock.api"),
+        (Self::LOCK_KEYBOARD, "dx.keypad.input.lock.api"),
+    ];
+
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, other: BindFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// 包含的单个选项
+    pub fn iter(self) -> impl Iterator<Item = BindFlags> {
+        Self::ALL
+            .iter()
+            .map(|(flag, _)| *flag)
+            .filter(move |flag| self.contains(*flag))
+    }
+}
+
+impl BitOr for BindFlags {
+    type Output = BindFlags;
+
+    fn bitor(self, rhs: BindFlags) -> BindFlags {
+        BindFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for BindFlags {
+    fn bitor_assign(&mut self, rhs: BindFlags) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl fmt::Display for BindFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = Self::ALL
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect();
+        f.write_str(&names.join("|"))
+    }
+}
+
+impl FromStr for BindFlags {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut flags = BindFlags::NONE;
+        for item in s.split('|').map(str::trim).filter(|item| !item.is_empty()) {
+            let (flag, _) = Self::ALL
+                .iter()
+                .find(|(_, name)| *name == item)
+                .ok_or_else(|| Error::InvalidArgument(format!("Flag \"{}\" 不存在", item)))?;
+            flags |= *flag;
+        }
+        Ok(flags)
+    }
+}
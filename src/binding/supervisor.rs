@@ -0,0 +1,210 @@
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use super::{BindMode, BindingGuard};
+use crate::{AoJia, Clock, Error, Hwnd, Result, SystemClock, WindowQuery};
+
+/// 重新绑定的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebindReason {
+    /// 绑定的窗口已关闭
+    WindowGone,
+    /// 后台操作连续失败的次数
+    OperationsFailed(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindingEvent {
+    /// 绑定到了新窗口，`old` 为之前绑定的窗口
+    Rebound {
+        old: Option<Hwnd>,
+        new: Hwnd,
+        reason: RebindReason,
+    },
+    /// 重新绑定失败，下次检查时会再试
+    RebindFailed {
+        old: Option<Hwnd>,
+        reason: RebindReason,
+        error: String,
+    },
+}
+
+/// 检查间隔、允许连续失败的次数和时钟
+#[derive(Clone)]
+pub struct SupervisorOptions {
+    pub interval: Duration,
+    pub max_failures: u32,
+    pub clock: Arc<dyn Clock>,
+}
+
+impl std::fmt::Debug for SupervisorOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SupervisorOptions")
+            .field("interval", &self.interval)
+            .field("max_failures", &self.max_failures)
+            .finish()
+    }
+}
+
+impl Default for SupervisorOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            max_failures: 3,
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+impl SupervisorOptions {
+    pub fn new(interval: Duration, max_failures: u32) -> Self {
+        Self {
+            interval,
+            max_failures: max_failures.max(1),
+            ..Default::default()
+        }
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+}
+
+/// 持有绑定并在窗口重建或后台操作失败时按查询条件重新绑定。
+///
+/// COM 对象不能跨线程使用，所以检查不在后台线程中进行，
+/// 需要定期调用 [`check`](Self::check)，或者用 [`run`](Self::run) 执行后台操作。
+#[derive(Debug)]
+pub struct BindingSupervisor<'a> {
+    aojia: &'a AoJia,
+    query: WindowQuery,
+    mode: BindMode,
+    options: SupervisorOptions,
+    guard: Option<BindingGuard<'a>>,
+    failures: u32,
+    last_check: Instant,
+    subscribers: Vec<Sender<BindingEvent>>,
+}
+
+impl<'a> BindingSupervisor<'a> {
+    /// 查找窗口并绑定，找不到或绑定失败时返回错误
+    pub fn new(
+        aojia: &'a AoJia,
+        query: WindowQuery,
+        mode: BindMode,
+        options: SupervisorOptions,
+    ) -> Result<Self> {
+        let hwnd = aojia.find_window(&query)?.ok_or(Error::Failed {
+            fun: "FindWindow",
+            ret: 0,
+        })?;
+        let guard = aojia.bind(hwnd, mode.clone())?;
+        let last_check = options.clock.now();
+        Ok(Self {
+            aojia,
+            query,
+            mode,
+            options,
+            guard: Some(guard),
+            failures: 0,
+            last_check,
+            subscribers: Vec::new(),
+        })
+    }
+
+    /// 当前绑定的窗口，重新绑定失败后为 None
+    pub fn hwnd(&self) -> Option<Hwnd> {
+        self.guard.as_ref().map(|g| g.hwnd())
+    }
+
+    pub fn mode(&self) -> &BindMode {
+        &self.mode
+    }
+
+    /// 接收之后发生的重新绑定事件
+    pub fn subscribe(&mut self) -> Receiver<BindingEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.push(tx);
+        rx
+    }
+
+    fn alive(&self, hwnd: Hwnd) -> Result<bool> {
+        let (mut width, mut height) = (0, 0);
+        Ok(self.aojia.GetClientSize(hwnd, &mut width, &mut height)? != 0)
+    }
+
+    /// 检查绑定的窗口是否还在，已关闭时重新绑定
+    pub fn check(&mut self) -> Result<Option<BindingEvent>> {
+        self.last_check = self.options.clock.now();
+        match self.hwnd() {
+            Some(hwnd) if self.alive(hwnd)? => Ok(None),
+            _ => Ok(Some(self.rebind(RebindReason::WindowGone))),
+        }
+    }
+
+    /// 记录一次失败的后台操作，连续失败达到上限时重新绑定
+    pub fn record_failure(&mut self) -> Option<BindingEvent> {
+        self.failures += 1;
+        if self.failures < self.options.max_failures {
+            return None;
+        }
+        Some(self.rebind(RebindReason::OperationsFailed(self.failures)))
+    }
+
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+    }
+
+    /// 执行一次后台操作：到了检查间隔时先检查窗口，再根据操作结果记录成功或失败
+    pub fn run<T>(&mut self, op: impl FnOnce(&AoJia) -> Result<T>) -> Result<T> {
+        let now = self.options.clock.now();
+        if now.duration_since(self.last_check) >= self.options.interval {
+            self.check()?;
+        }
+        let ret = op(self.aojia);
+        match ret {
+            Ok(_) => self.record_success(),
+            Err(_) => {
+                self.record_failure();
+            }
+        }
+        ret
+    }
+
+    /// 解绑后按查询条件重新查找窗口并用同样的模式绑定，失败时返回 [`BindingEvent::RebindFailed`]
+    pub fn rebind(&mut self, reason: RebindReason) -> BindingEvent {
+        let old = self.hwnd();
+        if let Some(guard) = self.guard.take() {
+            // 窗口已关闭时解绑可能失败，不影响重新绑定
+            let _ = guard.unbind();
+        }
+        self.failures = 0;
+        self.last_check = self.options.clock.now();
+        let bound = self
+            .aojia
+            .find_window(&self.query)
+            .and_then(|hwnd| {
+                hwnd.ok_or(Error::Failed {
+                    fun: "FindWindow",
+                    ret: 0,
+                })
+            })
+            .and_then(|hwnd| self.aojia.bind(hwnd, self.mode.clone()));
+        let event = match bound {
+            Ok(guard) => {
+                let new = guard.hwnd();
+                self.guard = Some(guard);
+                BindingEvent::Rebound { old, new, reason }
+            }
+            Err(e) => BindingEvent::RebindFailed {
+                old,
+                reason,
+                error: e.to_string(),
+            },
+        };
+        self.subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+        event
+    }
+}
@@ -0,0 +1,363 @@
+//! CompressFile 和 UnCompressFile 的封装。
+//!
+//! 插件返回 1 表示成功，其他返回值按下表转换为错误：
+//! - 0: 调用失败，[`Error::Failed`]
+//! - -1: 无法读取源文件，[`io::ErrorKind::NotFound`]
+//! - -2: 无法写入目标文件，[`io::ErrorKind::PermissionDenied`]
+//! - -3: 源文件不是对应格式的压缩数据，[`io::ErrorKind::InvalidData`]
+//!
+//! 插件只能压缩单个文件，[`AoJia::pack_dir`] 先把目录中的文件写入一个包文件，再压缩成一个文件。
+//! 包文件中的整数均为小端序：
+//! - 4 字节标识 `AJPK`，4 字节文件数
+//! - 每个文件依次为 4 字节路径长度、以 `/` 分隔的 UTF-8 相对路径、8 字节内容长度、文件内容
+//!
+//! 空目录不会保存。
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{AoJia, Error, Result};
+
+/// CompressFile 和 UnCompressFile 的 Type 参数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CompressionFormat {
+    #[default]
+    Zlib = 0,
+    Gzip = 1,
+    Zip = 2,
+}
+
+impl CompressionFormat {
+    /// 该格式的压缩文件常用的扩展名
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Zlib => "zlib",
+            Self::Gzip => "gz",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+impl From<CompressionFormat> for i32 {
+    fn from(format: CompressionFormat) -> Self {
+        format as i32
+    }
+}
+
+impl TryFrom<i32> for CompressionFormat {
+    type Error = Error;
+
+    fn try_from(ty: i32) -> Result<Self> {
+        match ty {
+            0 => Ok(Self::Zlib),
+            1 => Ok(Self::Gzip),
+            2 => Ok(Self::Zip),
+            _ => Err(Error::InvalidArgument(format!(
+                "Type {} 不在 0 ~ 2 之间",
+                ty
+            ))),
+        }
+    }
+}
+
+/// CompressFile 的 Level 参数，0 为只存储不压缩，9 为压缩率最高。
+///
+/// 比较和哈希按实际传给插件的级别进行，`Level(6)` 与 `Default` 相等
+#[derive(Debug, Clone, Copy, Default)]
+pub enum CompressionLevel {
+    Store,
+    Fastest,
+    #[default]
+    Default,
+    Best,
+    /// 0 ~ 9 之间的级别
+    Level(u8),
+}
+
+impl CompressionLevel {
+    pub fn validate(self) -> Result<()> {
+        match self {
+            Self::Level(level) if level > 9 => Err(Error::InvalidArgument(format!(
+                "压缩级别 {} 不在 0 ~ 9 之间",
+                level
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl PartialEq for CompressionLevel {
+    fn eq(&self, other: &Self) -> bool {
+        i32::from(*self) == i32::from(*other)
+    }
+}
+
+impl Eq for CompressionLevel {}
+
+impl std::hash::Hash for CompressionLevel {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        i32::from(*self).hash(state);
+    }
+}
+
+impl From<CompressionLevel> for i32 {
+    fn from(level: CompressionLevel) -> Self {
+        match level {
+            CompressionLevel::Store => 0,
+            CompressionLevel::Fastest => 1,
+            CompressionLevel::Default => 6,
+            CompressionLevel::Best => 9,
+            CompressionLevel::Level(level) => level as i32,
+        }
+    }
+}
+
+impl TryFrom<i32> for CompressionLevel {
+    type Error = Error;
+
+    fn try_from(level: i32) -> Result<Self> {
+        match level {
+            0 => Ok(Self::Store),
+            1 => Ok(Self::Fastest),
+            6 => Ok(Self::Default),
+            9 => Ok(Self::Best),
+            2..=8 => Ok(Self::Level(level as u8)),
+            _ => Err(Error::InvalidArgument(format!(
+                "压缩级别 {} 不在 0 ~ 9 之间",
+                level
+            ))),
+        }
+    }
+}
+
+/// 把插件的返回值转换为结果
+fn check(fun: &'static str, ret: i32, src: &Path, dst: &Path) -> Result<()> {
+    let (kind, msg) = match ret {
+        1 => return Ok(()),
+        -1 => (
+            io::ErrorKind::NotFound,
+            format!("无法读取 {}", src.display()),
+        ),
+        -2 => (
+            io::ErrorKind::PermissionDenied,
+            format!("无法写入 {}", dst.display()),
+        ),
+        -3 => (
+            io::ErrorKind::InvalidData,
+            format!("{} 不是有效的压缩数据", src.display()),
+        ),
+        _ => return Err(Error::Failed { fun, ret }),
+    };
+    Err(Error::Io(io::Error::new(
+        kind,
+        format!("{} 返回 {}: {}", fun, ret, msg),
+    )))
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| Error::InvalidArgument(format!("路径 {} 不是有效的 UTF-8", path.display())))
+}
+
+/// 检查源文件存在、目标所在目录存在，返回两者的字符串
+fn check_paths<'p>(src: &'p Path, dst: &'p Path) -> Result<(&'p str, &'p str)> {
+    if !src.is_file() {
+        return Err(Error::InvalidArgument(format!(
+            "源文件 {} 不存在",
+            src.display()
+        )));
+    }
+    if let Some(parent) = dst.parent().filter(|p| !p.as_os_str().is_empty())
+        && !parent.is_dir()
+    {
+        return Err(Error::InvalidArgument(format!(
+            "目标目录 {} 不存在",
+            parent.display()
+        )));
+    }
+    Ok((path_str(src)?, path_str(dst)?))
+}
+
+/// 目录下所有文件的相对路径，按路径排序
+fn files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Err(Error::InvalidArgument(format!(
+            "目录 {} 不存在",
+            dir.display()
+        )));
+    }
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        for entry in fs::read_dir(dir.join(&rel))? {
+            let entry = entry?;
+            let rel = rel.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                pending.push(rel);
+            } else {
+                files.push(rel);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+const PACK_MAGIC: &[u8; 4] = b"AJPK";
+
+/// 系统临时目录中的中间文件，离开作用域时删除
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(purpose: &str) -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        let name = format!("aojia-{}-{}-{}.tmp", purpose, std::process::id(), n);
+        Self(std::env::temp_dir().join(name))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// 包中以 `/` 分隔的相对路径
+fn pack_name(rel: &Path) -> Result<String> {
+    let parts = rel
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => path_str(Path::new(part)),
+            _ => unreachable!("files 只返回相对路径"),
+        })
+        .collect::<Result<Vec<&str>>>()?;
+    Ok(parts.join("/"))
+}
+
+fn write_pack(src: &Path, files: &[PathBuf], pack: &Path) -> Result<()> {
+    let count = u32::try_from(files.len())
+        .map_err(|_| Error::InvalidArgument(format!("目录 {} 中的文件过多", src.display())))?;
+    let mut out = io::BufWriter::new(fs::File::create(pack)?);
+    out.write_all(PACK_MAGIC)?;
+    out.write_all(&count.to_le_bytes())?;
+    for rel in files {
+        let name = pack_name(rel)?;
+        let data = fs::read(src.join(rel))?;
+        out.write_all(&(name.len() as u32).to_le_bytes())?;
+        out.write_all(name.as_bytes())?;
+        out.write_all(&(data.len() as u64).to_le_bytes())?;
+        out.write_all(&data)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn take<'a>(rest: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    let (head, tail) = rest.split_at_checked(n)?;
+    *rest = tail;
+    Some(head)
+}
+
+/// 解析包文件，格式不对或路径会指向目标目录之外时返回 None
+fn read_pack(data: &[u8]) -> Option<Vec<(PathBuf, &[u8])>> {
+    let mut rest = data.strip_prefix(PACK_MAGIC)?;
+    let count = u32::from_le_bytes(take(&mut rest, 4)?.try_into().ok()?);
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let len = u32::from_le_bytes(take(&mut rest, 4)?.try_into().ok()?);
+        let name = std::str::from_utf8(take(&mut rest, len as usize)?).ok()?;
+        let len = u64::from_le_bytes(take(&mut rest, 8)?.try_into().ok()?);
+        let data = take(&mut rest, usize::try_from(len).ok()?)?;
+
+        let mut rel = PathBuf::new();
+        for part in name.split('/') {
+            if part.is_empty() || part == "." || part == ".." || part.contains(['\\', ':']) {
+                return None;
+            }
+            rel.push(part);
+        }
+        entries.push((rel, data));
+    }
+    rest.is_empty().then_some(entries)
+}
+
+impl AoJia {
+    /// 压缩单个文件，目标所在的目录必须已存在
+    pub fn compress_file(
+        &self,
+        src: impl AsRef<Path>,
+        dst: impl AsRef<Path>,
+        format: CompressionFormat,
+        level: CompressionLevel,
+    ) -> Result<()> {
+        let (src, dst) = (src.as_ref(), dst.as_ref());
+        level.validate()?;
+        let (sf, df) = check_paths(src, dst)?;
+        let ret = self.CompressFile(sf, df, format.into(), level.into())?;
+        check("CompressFile", ret, src, dst)
+    }
+
+    /// 解压单个文件，目标所在的目录必须已存在
+    pub fn uncompress_file(
+        &self,
+        src: impl AsRef<Path>,
+        dst: impl AsRef<Path>,
+        format: CompressionFormat,
+    ) -> Result<()> {
+        let (src, dst) = (src.as_ref(), dst.as_ref());
+        let (sf, df) = check_paths(src, dst)?;
+        let ret = self.UnCompressFile(sf, df, format.into())?;
+        check("UnCompressFile", ret, src, dst)
+    }
+
+    /// 把 `src` 目录下的所有文件打包后压缩为一个文件 `dst`，返回打包的文件的相对路径
+    pub fn pack_dir(
+        &self,
+        src: impl AsRef<Path>,
+        dst: impl AsRef<Path>,
+        format: CompressionFormat,
+        level: CompressionLevel,
+    ) -> Result<Vec<PathBuf>> {
+        let (src, dst) = (src.as_ref(), dst.as_ref());
+        level.validate()?;
+        let files = files(src)?;
+        let pack = TempFile::new("pack");
+        write_pack(src, &files, &pack.0)?;
+        self.compress_file(&pack.0, dst, format, level)?;
+        Ok(files)
+    }
+
+    /// [`AoJia::pack_dir`] 的逆操作，把 `src` 解压到 `dst` 目录下，返回写入的文件。
+    /// 包中的路径不能指向 `dst` 之外，整个包检查通过后才会写入文件
+    pub fn unpack_dir(
+        &self,
+        src: impl AsRef<Path>,
+        dst: impl AsRef<Path>,
+        format: CompressionFormat,
+    ) -> Result<Vec<PathBuf>> {
+        let (src, dst) = (src.as_ref(), dst.as_ref());
+        let pack = TempFile::new("unpack");
+        self.uncompress_file(src, &pack.0, format)?;
+        let data = fs::read(&pack.0)?;
+        let entries = read_pack(&data).ok_or_else(|| {
+            Error::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} 不是有效的目录包", src.display()),
+            ))
+        })?;
+
+        let mut unpacked = Vec::with_capacity(entries.len());
+        for (rel, data) in entries {
+            let out = dst.join(rel);
+            if let Some(parent) = out.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&out, data)?;
+            unpacked.push(out);
+        }
+        Ok(unpacked)
+    }
+}
@@ -0,0 +1,197 @@
+//! 操作之间的随机延时。
+//!
+//! [`DelayPolicy`] 描述延时的分布，[`DelayScheduler`] 用可指定种子的 [`Rng`] 采样，
+//! 再用注入的时钟等待，也可以交给插件的 YanShi 等待。
+//! 用 [`AoJia::set_delay`] 设置后，点击、移动和按键等操作完成后都会等待一次。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::check;
+use crate::{AoJia, Clock, Error, Result, Rng, SystemClock};
+
+/// 延时的分布
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    Fixed(Duration),
+    /// [min, max) 之间均匀分布
+    Uniform {
+        min: Duration,
+        max: Duration,
+    },
+    Normal {
+        mean: Duration,
+        std_dev: Duration,
+    },
+    /// 对数正态分布，`median` 为中位数，`sigma` 为对数的标准差，多数延时较短，偶尔较长
+    LogNormal {
+        median: Duration,
+        sigma: f64,
+    },
+}
+
+/// 延时分布、上下限和速度倍率。
+///
+/// 采样值先限制在 `[min, max]` 之间，再除以 `speed`，`speed` 为 2 时所有延时减半。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DelayPolicy {
+    pub distribution: Distribution,
+    pub min: Duration,
+    pub max: Duration,
+    pub speed: f64,
+}
+
+impl DelayPolicy {
+    pub fn new(distribution: Distribution) -> Self {
+        Self {
+            distribution,
+            min: Duration::ZERO,
+            max: Duration::MAX,
+            speed: 1.0,
+        }
+    }
+
+    pub fn fixed(delay: Duration) -> Self {
+        Self::new(Distribution::Fixed(delay))
+    }
+
+    pub fn uniform(min: Duration, max: Duration) -> Self {
+        Self::new(Distribution::Uniform { min, max })
+    }
+
+    pub fn normal(mean: Duration, std_dev: Duration) -> Self {
+        Self::new(Distribution::Normal { mean, std_dev })
+    }
+
+    pub fn log_normal(median: Duration, sigma: f64) -> Self {
+        Self::new(Distribution::LogNormal { median, sigma })
+    }
+
+    pub fn clamp(mut self, min: Duration, max: Duration) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: &str| Err(Error::InvalidArgument(format!("{}: {:?}", msg, self)));
+        if self.min > self.max {
+            return invalid("延时下限大于上限");
+        }
+        if !(self.speed.is_finite() && self.speed > 0.0) {
+            return invalid("速度倍率必须大于 0");
+        }
+        match self.distribution {
+            Distribution::Uniform { min, max } if min > max => invalid("均匀分布下限大于上限"),
+            Distribution::LogNormal { sigma, .. } if !(sigma.is_finite() && sigma >= 0.0) => {
+                invalid("对数正态分布的 sigma 不能为负")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 采样一次延时
+    pub fn sample(&self, rng: &mut Rng) -> Duration {
+        let secs = match self.distribution {
+            Distribution::Fixed(d) => d.as_secs_f64(),
+            Distribution::Uniform { min, max } => rng.uniform(min.as_secs_f64(), max.as_secs_f64()),
+            Distribution::Normal { mean, std_dev } => {
+                mean.as_secs_f64() + rng.normal() * std_dev.as_secs_f64()
+            }
+            Distribution::LogNormal { median, sigma } => {
+                median.as_secs_f64() * (rng.normal() * sigma).exp()
+            }
+        };
+        let clamped = secs
+            .max(self.min.as_secs_f64())
+            .min(self.max.as_secs_f64())
+            .max(0.0);
+        Duration::try_from_secs_f64(clamped / self.speed).unwrap_or(self.max)
+    }
+}
+
+/// 按策略采样并等待
+#[derive(Clone)]
+pub struct DelayScheduler {
+    pub policy: DelayPolicy,
+    rng: Rng,
+    clock: Arc<dyn Clock>,
+    yanshi: bool,
+}
+
+impl std::fmt::Debug for DelayScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DelayScheduler")
+            .field("policy", &self.policy)
+            .field("yanshi", &self.yanshi)
+            .finish()
+    }
+}
+
+impl DelayScheduler {
+    /// 以当前时间为种子
+    pub fn new(policy: DelayPolicy) -> Result<Self> {
+        policy.validate()?;
+        Ok(Self {
+            policy,
+            rng: Rng::default(),
+            clock: Arc::new(SystemClock),
+            yanshi: false,
+        })
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// 采样后调用插件的 YanShi 等待，而不是在 Rust 中等待
+    pub fn use_yanshi(mut self, yanshi: bool) -> Self {
+        self.yanshi = yanshi;
+        self
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        self.policy.sample(&mut self.rng)
+    }
+
+    /// 采样并等待，返回等待的时间
+    pub fn wait(&mut self, aojia: &AoJia) -> Result<Duration> {
+        let delay = self.next_delay();
+        if self.yanshi {
+            let ms = delay.as_millis().min(i32::MAX as u128) as i32;
+            check("YanShi", aojia.YanShi(ms, ms)?)?;
+        } else {
+            self.clock.sleep(delay);
+        }
+        Ok(delay)
+    }
+}
+
+impl AoJia {
+    /// 设置操作之间的延时，None 表示不等待
+    pub fn set_delay(&self, scheduler: Option<DelayScheduler>) {
+        *self.delay.borrow_mut() = scheduler;
+    }
+
+    /// 按设置的延时等待一次，没有设置时立即返回
+    pub fn pause(&self) -> Result<()> {
+        let scheduler = self.delay.borrow_mut().take();
+        let Some(mut scheduler) = scheduler else {
+            return Ok(());
+        };
+        let ret = scheduler.wait(self);
+        *self.delay.borrow_mut() = Some(scheduler);
+        ret.map(|_| ())
+    }
+}
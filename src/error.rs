@@ -0,0 +1,87 @@
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum Error {
+    /// COM 调用失败
+    #[cfg(windows)]
+    Com(windows::core::Error),
+    /// 插件返回值无法转换为所需类型
+    Convert(String),
+    /// 传给插件函数的参数不合法
+    InvalidArgument(String),
+    /// 图片文件无法解析
+    Image(String),
+    /// 后端不支持该调用
+    Unsupported(String),
+    /// 等待超时
+    Timeout(Duration),
+    /// 等待被取消
+    Cancelled,
+    /// 插件函数返回了表示失败的值
+    Failed {
+        fun: &'static str,
+        ret: i32,
+    },
+    /// 已绑定了窗口，需要先解绑
+    AlreadyBound(i32),
+    /// 窗口已关闭或句柄无效
+    WindowGone(i32),
+    /// 画面与预期不符
+    Mismatch(String),
+    Io(std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// 插件函数返回 0 表示失败
+pub(crate) fn check(fun: &'static str, ret: i32) -> Result<()> {
+    if ret == 0 {
+        return Err(Error::Failed { fun, ret });
+    }
+    Ok(())
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(windows)]
+            Error::Com(e) => write!(f, "COM 调用失败: {}", e),
+            Error::Convert(msg) => write!(f, "类型转换失败: {}", msg),
+            Error::InvalidArgument(msg) => write!(f, "参数不合法: {}", msg),
+            Error::Image(msg) => write!(f, "图片解析失败: {}", msg),
+            Error::Unsupported(msg) => write!(f, "不支持的调用: {}", msg),
+            Error::Timeout(d) => write!(f, "等待 {:?} 后超时", d),
+            Error::Cancelled => write!(f, "等待被取消"),
+            Error::Failed { fun, ret } => write!(f, "{} 调用失败，返回 {}", fun, ret),
+            Error::AlreadyBound(hwnd) => write!(f, "已绑定窗口 {}，需要先解绑", hwnd),
+            Error::WindowGone(hwnd) => write!(f, "窗口 {} 已关闭", hwnd),
+            Error::Mismatch(msg) => write!(f, "画面与预期不符: {}", msg),
+            Error::Io(e) => write!(f, "IO 错误: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(windows)]
+            Error::Com(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl From<windows::core::Error> for Error {
+    fn from(e: windows::core::Error) -> Self {
+        Error::Com(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
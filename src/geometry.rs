@@ -0,0 +1,299 @@
+use crate::error::check;
+use crate::{AoJia, Error, Hwnd, Result};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn offset(self, dx: i32, dy: i32) -> Self {
+        Self::new(self.x + dx, self.y + dy)
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+/// 与插件一致的矩形，`x2, y2` 包含在内
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x1: i32,
+    pub y1: i32,
+    pub x2: i32,
+    pub y2: i32,
+}
+
+impl Rect {
+    pub fn new(x1: i32, y1: i32, x2: i32, y2: i32) -> Self {
+        Self { x1, y1, x2, y2 }
+    }
+
+    /// 以 (x, y) 为左上角、宽 `width` 高 `height` 的矩形
+    pub fn from_size(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self::new(x, y, x + width - 1, y + height - 1)
+    }
+
+    pub fn width(&self) -> i32 {
+        self.x2 - self.x1 + 1
+    }
+
+    pub fn height(&self) -> i32 {
+        self.y2 - self.y1 + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width() <= 0 || self.height() <= 0
+    }
+
+    pub fn top_left(&self) -> Point {
+        Point::new(self.x1, self.y1)
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new((self.x1 + self.x2) / 2, (self.y1 + self.y2) / 2)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.x1..=self.x2).contains(&point.x) && (self.y1..=self.y2).contains(&point.y)
+    }
+
+    pub fn offset(&self, dx: i32, dy: i32) -> Self {
+        Self::new(self.x1 + dx, self.y1 + dy, self.x2 + dx, self.y2 + dy)
+    }
+
+    /// 与另一矩形的交集，不相交时返回 None
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let rect = Rect::new(
+            self.x1.max(other.x1),
+            self.y1.max(other.y1),
+            self.x2.min(other.x2),
+            self.y2.min(other.y2),
+        );
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// 把矩形等分为 `cols` 列 `rows` 行，返回第 `col` 列第 `row` 行，均从 0 开始
+    pub fn grid(&self, cols: u32, rows: u32, col: u32, row: u32) -> Result<Rect> {
+        if col >= cols || row >= rows {
+            return Err(Error::InvalidArgument(format!(
+                "格子 ({}, {}) 不在 {}x{} 网格内",
+                col, row, cols, rows
+            )));
+        }
+        let (w, h) = (self.width() as i64, self.height() as i64);
+        let edge =
+            |start: i32, len: i64, n: u32, i: u32| start + (len * i as i64 / n as i64) as i32;
+        Ok(Rect::new(
+            edge(self.x1, w, cols, col),
+            edge(self.y1, h, rows, row),
+            edge(self.x1, w, cols, col + 1) - 1,
+            edge(self.y1, h, rows, row + 1) - 1,
+        ))
+    }
+
+    /// 按比例截取子区域，参数为 0.0 ~ 1.0 之间的左、上、右、下边界
+    pub fn percent(&self, left: f64, top: f64, right: f64, bottom: f64) -> Result<Rect> {
+        let valid = |v: f64| (0.0..=1.0).contains(&v);
+        if ![left, top, right, bottom].into_iter().all(valid) || left >= right || top >= bottom {
+            return Err(Error::InvalidArgument(format!(
+                "比例 ({}, {}, {}, {}) 不合法",
+                left, top, right, bottom
+            )));
+        }
+        let (w, h) = (self.width() as f64, self.height() as f64);
+        Ok(Rect::new(
+            self.x1 + (w * left).round() as i32,
+            self.y1 + (h * top).round() as i32,
+            self.x1 + (w * right).round() as i32 - 1,
+            self.y1 + (h * bottom).round() as i32 - 1,
+        ))
+    }
+}
+
+impl From<(i32, i32, i32, i32)> for Rect {
+    fn from((x1, y1, x2, y2): (i32, i32, i32, i32)) -> Self {
+        Self::new(x1, y1, x2, y2)
+    }
+}
+
+impl From<Rect> for (i32, i32, i32, i32) {
+    fn from(rect: Rect) -> Self {
+        (rect.x1, rect.y1, rect.x2, rect.y2)
+    }
+}
+
+/// 坐标所在的坐标系
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CoordSpace {
+    Screen,
+    /// 窗口客户区，左上角为 (0, 0)
+    Client(Hwnd),
+}
+
+/// ClientOrScreen 的 Type 参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CoordConversion {
+    /// 客户区坐标转为屏幕坐标
+    ClientToScreen = 0,
+    /// 屏幕坐标转为客户区坐标
+    ScreenToClient = 1,
+}
+
+impl From<CoordConversion> for i32 {
+    fn from(conversion: CoordConversion) -> Self {
+        conversion as i32
+    }
+}
+
+/// 带坐标系的矩形
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub space: CoordSpace,
+    pub rect: Rect,
+}
+
+impl Region {
+    pub fn screen(rect: impl Into<Rect>) -> Self {
+        Self {
+            space: CoordSpace::Screen,
+            rect: rect.into(),
+        }
+    }
+
+    pub fn client(hwnd: Hwnd, rect: impl Into<Rect>) -> Self {
+        Self {
+            space: CoordSpace::Client(hwnd),
+            rect: rect.into(),
+        }
+    }
+
+    pub fn grid(&self, cols: u32, rows: u32, col: u32, row: u32) -> Result<Region> {
+        Ok(Self {
+            rect: self.rect.grid(cols, rows, col, row)?,
+            ..*self
+        })
+    }
+
+    pub fn percent(&self, left: f64, top: f64, right: f64, bottom: f64) -> Result<Region> {
+        Ok(Self {
+            rect: self.rect.percent(left, top, right, bottom)?,
+            ..*self
+        })
+    }
+}
+
+impl AoJia {
+    /// 窗口客户区左上角的屏幕坐标
+    pub fn client_origin(&self, hwnd: Hwnd) -> Result<Point> {
+        let (mut x, mut y) = (0, 0);
+        check("ClientToScreen", self.ClientToScreen(hwnd, &mut x, &mut y)?)?;
+        Ok(Point::new(x, y))
+    }
+
+    /// 用 ClientOrScreen 转换一个点
+    pub fn convert_point(
+        &self,
+        hwnd: Hwnd,
+        point: Point,
+        conversion: CoordConversion,
+    ) -> Result<Point> {
+        let (mut x, mut y) = (0, 0);
+        let ret = self.ClientOrScreen(hwnd, point.x, point.y, &mut x, &mut y, conversion.into())?;
+        check("ClientOrScreen", ret)?;
+        Ok(Point::new(x, y))
+    }
+
+    pub fn client_to_screen(&self, hwnd: Hwnd, point: Point) -> Result<Point> {
+        self.convert_point(hwnd, point, CoordConversion::ClientToScreen)
+    }
+
+    pub fn screen_to_client(&self, hwnd: Hwnd, point: Point) -> Result<Point> {
+        self.convert_point(hwnd, point, CoordConversion::ScreenToClient)
+    }
+
+    /// 批量转换，只调用一次插件取得两个坐标系间的偏移。
+    ///
+    /// 客户区坐标与屏幕坐标之间只差一个平移，所以先转换 (0, 0)，
+    /// 再把结果作为偏移加到每个点上。转换期间窗口移动时，结果对应调用插件时的位置
+    pub fn convert_points(
+        &self,
+        hwnd: Hwnd,
+        points: &[Point],
+        conversion: CoordConversion,
+    ) -> Result<Vec<Point>> {
+        let offset = self.convert_point(hwnd, Point::default(), conversion)?;
+        Ok(points
+            .iter()
+            .map(|p| p.offset(offset.x, offset.y))
+            .collect())
+    }
+
+    /// 批量转换矩形，偏移的取得方式与 [`AoJia::convert_points`] 相同
+    pub fn convert_rects(
+        &self,
+        hwnd: Hwnd,
+        rects: &[Rect],
+        conversion: CoordConversion,
+    ) -> Result<Vec<Rect>> {
+        let offset = self.convert_point(hwnd, Point::default(), conversion)?;
+        Ok(rects.iter().map(|r| r.offset(offset.x, offset.y)).collect())
+    }
+
+    /// 由 GetClientSize 得到的整个客户区
+    pub fn client_region(&self, hwnd: Hwnd) -> Result<Region> {
+        let (mut width, mut height) = (0, 0);
+        check(
+            "GetClientSize",
+            self.GetClientSize(hwnd, &mut width, &mut height)?,
+        )?;
+        Ok(Region::client(hwnd, Rect::from_size(0, 0, width, height)))
+    }
+
+    pub fn to_screen(&self, region: &Region) -> Result<Region> {
+        match region.space {
+            CoordSpace::Screen => Ok(*region),
+            CoordSpace::Client(hwnd) => {
+                let origin = self.client_origin(hwnd)?;
+                Ok(Region::screen(region.rect.offset(origin.x, origin.y)))
+            }
+        }
+    }
+
+    pub fn to_client(&self, region: &Region, hwnd: Hwnd) -> Result<Region> {
+        if region.space == CoordSpace::Client(hwnd) {
+            return Ok(*region);
+        }
+        let screen = self.to_screen(region)?;
+        let origin = self.client_origin(hwnd)?;
+        Ok(Region::client(
+            hwnd,
+            screen.rect.offset(-origin.x, -origin.y),
+        ))
+    }
+
+    /// 把区域裁剪到窗口客户区内，结果仍在原来的坐标系中
+    pub fn clamp_to_client(&self, region: &Region, hwnd: Hwnd) -> Result<Region> {
+        let client = self.client_region(hwnd)?;
+        let rect = self
+            .to_client(region, hwnd)?
+            .rect
+            .intersect(&client.rect)
+            .ok_or_else(|| {
+                Error::InvalidArgument(format!("区域 {:?} 在窗口 {} 客户区外", region, hwnd))
+            })?;
+        let clamped = Region::client(hwnd, rect);
+        match region.space {
+            CoordSpace::Screen => self.to_screen(&clamped),
+            CoordSpace::Client(h) => self.to_client(&clamped, h),
+        }
+    }
+}
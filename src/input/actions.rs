@@ -0,0 +1,165 @@
+//! 由移动、按键和找图组合成的常用操作。
+//!
+//! 目标点会按 [`ActionOptions::jitter`] 随机偏移，设置了 [`ActionOptions::trajectory`] 时沿拟人轨迹移动，
+//! 操作完成后按 [`ActionOptions::verify`] 等待画面变化，超时返回 [`Error::Timeout`](crate::Error::Timeout)。
+
+use std::path::Path;
+
+use super::{MouseButton, Trajectory};
+use crate::pic::{Bitmap, PicMatch, PicSearch, WaitOptions};
+use crate::{AoJia, Point, Result, Rng};
+
+/// 操作完成后的检查
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Verify {
+    #[default]
+    Nothing,
+    /// 等待图片出现
+    PicAppears(PicSearch),
+    /// 等待图片消失
+    PicGone(PicSearch),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ActionOptions {
+    /// 目标点在两个方向上随机偏移的最大像素数
+    pub jitter: i32,
+    /// 为 None 时直接 MoveTo 到目标点
+    pub trajectory: Option<Trajectory>,
+    pub verify: Verify,
+    /// 检查时的超时和轮询间隔
+    pub wait: WaitOptions,
+}
+
+impl ActionOptions {
+    pub fn jitter(mut self, jitter: i32) -> Self {
+        self.jitter = jitter.max(0);
+        self
+    }
+
+    pub fn trajectory(mut self, trajectory: Trajectory) -> Self {
+        self.trajectory = Some(trajectory);
+        self
+    }
+
+    pub fn verify(mut self, verify: Verify, wait: WaitOptions) -> Self {
+        self.verify = verify;
+        self.wait = wait;
+        self
+    }
+
+    fn offset(&self, point: Point, rng: &mut Rng) -> Point {
+        point.offset(
+            rng.range(-self.jitter, self.jitter),
+            rng.range(-self.jitter, self.jitter),
+        )
+    }
+}
+
+impl AoJia {
+    /// SetPath 目录中图片的宽和高，文件读取失败时返回 [`Error::Io`](crate::Error::Io)，
+    /// 不是有效的 BMP 时返回 [`Error::Image`](crate::Error::Image)
+    pub fn pic_size(&self, pic_name: &str) -> Result<(u32, u32)> {
+        let path = Path::new(&self.GetPath()?).join(pic_name);
+        let pic = Bitmap::open(path)?;
+        Ok((pic.width(), pic.height()))
+    }
+
+    /// 鼠标当前位置，绑定了窗口时转换为 MoveTo 使用的客户区坐标
+    fn move_origin(&self) -> Result<Point> {
+        let pos = self.cursor_pos()?;
+        match self.binding() {
+            Some(binding) => self.screen_to_client(binding.hwnd, pos),
+            None => Ok(pos),
+        }
+    }
+
+    fn move_for_action(&self, to: Point, options: &ActionOptions, rng: &mut Rng) -> Result<()> {
+        match &options.trajectory {
+            Some(trajectory) => {
+                let from = self.move_origin()?;
+                self.human_move(from, to, trajectory, rng)
+            }
+            None => self.move_to(to),
+        }
+    }
+
+    /// 移动到 `target` 并单击，不再随机偏移
+    fn click_target(
+        &self,
+        target: Point,
+        button: MouseButton,
+        options: &ActionOptions,
+        rng: &mut Rng,
+    ) -> Result<()> {
+        self.move_for_action(target, options, rng)?;
+        self.click(button)?;
+        self.verify_action(options)
+    }
+
+    fn verify_action(&self, options: &ActionOptions) -> Result<()> {
+        match &options.verify {
+            Verify::Nothing => Ok(()),
+            Verify::PicAppears(search) => self.wait_for_pic(search, &options.wait).map(|_| ()),
+            Verify::PicGone(search) => self.wait_until_gone(search, &options.wait),
+        }
+    }
+
+    /// 移动到 `point` 附近并单击，返回实际点击的位置
+    pub fn click_at(
+        &self,
+        point: Point,
+        button: MouseButton,
+        options: &ActionOptions,
+        rng: &mut Rng,
+    ) -> Result<Point> {
+        let target = options.offset(point, rng);
+        self.click_target(target, button, options, rng)?;
+        Ok(target)
+    }
+
+    /// 按住左键从 `from` 拖到 `to`，中途出错时松开左键
+    pub fn drag(
+        &self,
+        from: Point,
+        to: Point,
+        options: &ActionOptions,
+        rng: &mut Rng,
+    ) -> Result<()> {
+        let (from, to) = (options.offset(from, rng), options.offset(to, rng));
+        self.move_for_action(from, options, rng)?;
+        self.mouse_down(MouseButton::Left)?;
+        let moved = match &options.trajectory {
+            Some(trajectory) => self.human_move(from, to, trajectory, rng),
+            None => self.move_to(to),
+        };
+        if let Err(e) = moved {
+            let _ = self.mouse_up(MouseButton::Left);
+            return Err(e);
+        }
+        self.mouse_up(MouseButton::Left)?;
+        self.verify_action(options)
+    }
+
+    /// 找图并点击，没找到时返回 None。
+    ///
+    /// 点击图片中间一半范围内的随机位置，不再叠加 [`ActionOptions::jitter`]。
+    /// 需要从 SetPath 目录读取图片大小，读取失败时返回 [`AoJia::pic_size`] 的错误，不会点击。
+    pub fn click_pic(
+        &self,
+        search: &PicSearch,
+        button: MouseButton,
+        options: &ActionOptions,
+        rng: &mut Rng,
+    ) -> Result<Option<PicMatch>> {
+        let Some(found) = self.find_pic(search)? else {
+            return Ok(None);
+        };
+        let (w, h) = self.pic_size(&found.name)?;
+        let (w, h) = (w as i32, h as i32);
+        let target = Point::new(found.x, found.y)
+            .offset(rng.range(w / 4, w * 3 / 4), rng.range(h / 4, h * 3 / 4));
+        self.click_target(target, button, options, rng)?;
+        Ok(Some(found))
+    }
+}
@@ -0,0 +1,296 @@
+//! 键盘输入。
+//!
+//! [`Key`] 对应插件使用的按键名和虚拟键码，[`Chord`] 解析 `ctrl+shift+F5` 这样的组合键，
+//! 按顺序按下、再按相反顺序弹起。小键盘按键名为 `numpad1` 这样的形式，
+//! 标点键直接用符号，如 `ctrl+-`；`+` 是分隔符，小键盘加号键写作 `add`。
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::check;
+use crate::{AoJia, Error, Result};
+
+macro_rules! keys {
+    ($($variant:ident => $vk:literal, $name:literal $(, $alias:literal)*;)+) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Key {
+            $($variant,)+
+        }
+
+        impl Key {
+            pub const ALL: &'static [Key] = &[$(Key::$variant,)+];
+
+            /// 虚拟键码
+            pub fn vk(self) -> i32 {
+                match self {
+                    $(Key::$variant => $vk,)+
+                }
+            }
+
+            /// 插件使用的按键名
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Key::$variant => $name,)+
+                }
+            }
+
+            /// 按键名或别名，不区分大小写
+            pub fn from_name(name: &str) -> Option<Key> {
+                match name.trim().to_ascii_lowercase().as_str() {
+                    $($name $(| $alias)* => Some(Key::$variant),)+
+                    _ => None,
+                }
+            }
+
+            pub fn from_vk(vk: i32) -> Option<Key> {
+                match vk {
+                    $($vk => Some(Key::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+keys! {
+        A => 0x41, "a";
+        B => 0x42, "b";
+        C => 0x43, "c";
+        D => 0x44, "d";
+        E => 0x45, "e";
+        F => 0x46, "f";
+        G => 0x47, "g";
+        H => 0x48, "h";
+        I => 0x49, "i";
+        J => 0x4A, "j";
+        K => 0x4B, "k";
+        L => 0x4C, "l";
+        M => 0x4D, "m";
+        N => 0x4E, "n";
+        O => 0x4F, "o";
+        P => 0x50, "p";
+        Q => 0x51, "q";
+        R => 0x52, "r";
+        S => 0x53, "s";
+        T => 0x54, "t";
+        U => 0x55, "u";
+        V => 0x56, "v";
+        W => 0x57, "w";
+        X => 0x58, "x";
+        Y => 0x59, "y";
+        Z => 0x5A, "z";
+        Num0 => 0x30, "0";
+        Num1 => 0x31, "1";
+        Num2 => 0x32, "2";
+        Num3 => 0x33, "3";
+        Num4 => 0x34, "4";
+        Num5 => 0x35, "5";
+        Num6 => 0x36, "6";
+        Num7 => 0x37, "7";
+        Num8 => 0x38, "8";
+        Num9 => 0x39, "9";
+        F1 => 0x70, "f1";
+        F2 => 0x71, "f2";
+        F3 => 0x72, "f3";
+        F4 => 0x73, "f4";
+        F5 => 0x74, "f5";
+        F6 => 0x75, "f6";
+        F7 => 0x76, "f7";
+        F8 => 0x77, "f8";
+        F9 => 0x78, "f9";
+        F10 => 0x79, "f10";
+        F11 => 0x7A, "f11";
+        F12 => 0x7B, "f12";
+        Ctrl => 0x11, "ctrl", "control";
+        Shift => 0x10, "shift";
+        Alt => 0x12, "alt", "menu";
+        Win => 0x5B, "win", "lwin";
+        Enter => 0x0D, "enter", "return";
+        Esc => 0x1B, "esc", "escape";
+        Tab => 0x09, "tab";
+        Space => 0x20, "space";
+        Backspace => 0x08, "back", "backspace";
+        Delete => 0x2E, "delete", "del";
+        Insert => 0x2D, "insert", "ins";
+        Home => 0x24, "home";
+        End => 0x23, "end";
+        PageUp => 0x21, "pageup", "pgup";
+        PageDown => 0x22, "pagedown", "pgdn";
+        Left => 0x25, "left";
+        Up => 0x26, "up";
+        Right => 0x27, "right";
+        Down => 0x28, "down";
+        CapsLock => 0x14, "capslock", "caps";
+        Numpad0 => 0x60, "numpad0", "num0";
+        Numpad1 => 0x61, "numpad1", "num1";
+        Numpad2 => 0x62, "numpad2", "num2";
+        Numpad3 => 0x63, "numpad3", "num3";
+        Numpad4 => 0x64, "numpad4", "num4";
+        Numpad5 => 0x65, "numpad5", "num5";
+        Numpad6 => 0x66, "numpad6", "num6";
+        Numpad7 => 0x67, "numpad7", "num7";
+        Numpad8 => 0x68, "numpad8", "num8";
+        Numpad9 => 0x69, "numpad9", "num9";
+        Multiply => 0x6A, "multiply", "numpad*";
+        Add => 0x6B, "add";
+        Subtract => 0x6D, "subtract", "numpad-";
+        Decimal => 0x6E, "decimal", "numpad.";
+        Divide => 0x6F, "divide", "numpad/";
+        Semicolon => 0xBA, ";", "semicolon";
+        Equal => 0xBB, "=", "equal";
+        Comma => 0xBC, ",", "comma";
+        Minus => 0xBD, "-", "minus";
+        Period => 0xBE, ".", "period";
+        Slash => 0xBF, "/", "slash";
+        Backquote => 0xC0, "`", "backquote";
+        LeftBracket => 0xDB, "[", "lbracket";
+        Backslash => 0xDC, "\\", "backslash";
+        RightBracket => 0xDD, "]", "rbracket";
+        Quote => 0xDE, "'", "quote";
+}
+
+impl Key {
+    pub fn is_modifier(self) -> bool {
+        matches!(self, Key::Ctrl | Key::Shift | Key::Alt | Key::Win)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Key {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Key::from_name(s).ok_or_else(|| Error::InvalidArgument(format!("按键 \"{}\" 不存在", s)))
+    }
+}
+
+impl From<Key> for i32 {
+    fn from(key: Key) -> Self {
+        key.vk()
+    }
+}
+
+impl TryFrom<i32> for Key {
+    type Error = Error;
+
+    fn try_from(vk: i32) -> Result<Self> {
+        Key::from_vk(vk)
+            .ok_or_else(|| Error::InvalidArgument(format!("虚拟键码 {:#04x} 不存在", vk)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyEvent {
+    Down(Key),
+    Up(Key),
+}
+
+/// 组合键，按书写顺序按下，按相反顺序弹起
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chord(Vec<Key>);
+
+impl Chord {
+    pub fn new(keys: Vec<Key>) -> Result<Self> {
+        if keys.is_empty() {
+            return Err(Error::InvalidArgument("组合键为空".to_string()));
+        }
+        for (i, key) in keys.iter().enumerate() {
+            if keys[..i].contains(key) {
+                return Err(Error::InvalidArgument(format!("组合键中 {} 重复", key)));
+            }
+        }
+        Ok(Self(keys))
+    }
+
+    pub fn keys(&self) -> &[Key] {
+        &self.0
+    }
+
+    /// 展开为按下和弹起的顺序
+    pub fn events(&self) -> Vec<KeyEvent> {
+        let downs = self.0.iter().map(|k| KeyEvent::Down(*k));
+        let ups = self.0.iter().rev().map(|k| KeyEvent::Up(*k));
+        downs.chain(ups).collect()
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self.0.iter().map(|k| k.name()).collect();
+        f.write_str(&names.join("+"))
+    }
+}
+
+impl FromStr for Chord {
+    type Err = Error;
+
+    /// 用 `+` 连接按键名，如 `ctrl+shift+F5`
+    fn from_str(s: &str) -> Result<Self> {
+        let keys = s
+            .split('+')
+            .map(|part| {
+                if part.trim().is_empty() {
+                    return Err(Error::InvalidArgument(format!("组合键 \"{}\" 中有空项", s)));
+                }
+                part.parse()
+            })
+            .collect::<Result<Vec<Key>>>()?;
+        Self::new(keys)
+    }
+}
+
+impl AoJia {
+    pub fn key_press(&self, key: Key) -> Result<()> {
+        check("KeyPress", self.KeyPress(key.vk())?)?;
+        self.pause()
+    }
+
+    pub fn key_down(&self, key: Key) -> Result<()> {
+        check("KeyDown", self.KeyDown(key.vk())?)?;
+        self.pause()
+    }
+
+    pub fn key_up(&self, key: Key) -> Result<()> {
+        check("KeyUp", self.KeyUp(key.vk())?)?;
+        self.pause()
+    }
+
+    /// 按键是否处于按下状态
+    pub fn key_state(&self, key: Key) -> Result<bool> {
+        Ok(self.GetKeyState(key.vk())? != 0)
+    }
+
+    /// 逐个字符输入字符串，`delay` 为每个字符之间的毫秒数
+    pub fn type_text(&self, text: &str, delay: i32) -> Result<()> {
+        check("KeyPressStr", self.KeyPressStr(text, delay)?)?;
+        self.pause()
+    }
+
+    /// 按下并弹起组合键，中途失败时弹起已按下的键
+    pub fn press_chord(&self, chord: &Chord) -> Result<()> {
+        let mut pressed = Vec::new();
+        for key in chord.keys() {
+            if let Err(e) = self.key_down(*key) {
+                for key in pressed.iter().rev() {
+                    let _ = self.key_up(*key);
+                }
+                return Err(e);
+            }
+            pressed.push(*key);
+        }
+        // 某个键弹起失败时仍然弹起其余的键，返回第一个错误
+        let mut result = Ok(());
+        for key in pressed.iter().rev() {
+            let released = self.key_up(*key);
+            if result.is_ok() {
+                result = released;
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,9 @@
+mod actions;
+mod keyboard;
+mod mouse;
+mod trajectory;
+
+pub use actions::{ActionOptions, Verify};
+pub use keyboard::{Chord, Key, KeyEvent};
+pub use mouse::MouseButton;
+pub use trajectory::{PathStep, Trajectory};
@@ -0,0 +1,122 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::check;
+use crate::{AoJia, Error, Point, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl MouseButton {
+    pub fn name(self) -> &'static str {
+        match self {
+            MouseButton::Left => "left",
+            MouseButton::Right => "right",
+            MouseButton::Middle => "middle",
+        }
+    }
+}
+
+impl fmt::Display for MouseButton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for MouseButton {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "left" => Ok(MouseButton::Left),
+            "right" => Ok(MouseButton::Right),
+            "middle" => Ok(MouseButton::Middle),
+            _ => Err(Error::InvalidArgument(format!("鼠标按键 \"{}\" 不存在", s))),
+        }
+    }
+}
+
+impl AoJia {
+    pub fn click(&self, button: MouseButton) -> Result<()> {
+        let (fun, ret) = match button {
+            MouseButton::Left => ("LeftClick", self.LeftClick()?),
+            MouseButton::Right => ("RightClick", self.RightClick()?),
+            MouseButton::Middle => ("MiddleClick", self.MiddleClick()?),
+        };
+        check(fun, ret)?;
+        self.pause()
+    }
+
+    /// 左键使用 LeftDoubleClick，其他按键连续单击两次，两次单击之间不停顿
+    pub fn double_click(&self, button: MouseButton) -> Result<()> {
+        match button {
+            MouseButton::Left => check("LeftDoubleClick", self.LeftDoubleClick()?)?,
+            MouseButton::Right => {
+                check("RightClick", self.RightClick()?)?;
+                check("RightClick", self.RightClick()?)?;
+            }
+            MouseButton::Middle => {
+                check("MiddleClick", self.MiddleClick()?)?;
+                check("MiddleClick", self.MiddleClick()?)?;
+            }
+        }
+        self.pause()
+    }
+
+    pub fn mouse_down(&self, button: MouseButton) -> Result<()> {
+        let (fun, ret) = match button {
+            MouseButton::Left => ("LeftDown", self.LeftDown()?),
+            MouseButton::Right => ("RightDown", self.RightDown()?),
+            MouseButton::Middle => ("MiddleDown", self.MiddleDown()?),
+        };
+        check(fun, ret)?;
+        self.pause()
+    }
+
+    pub fn mouse_up(&self, button: MouseButton) -> Result<()> {
+        let (fun, ret) = match button {
+            MouseButton::Left => ("LeftUp", self.LeftUp()?),
+            MouseButton::Right => ("RightUp", self.RightUp()?),
+            MouseButton::Middle => ("MiddleUp", self.MiddleUp()?),
+        };
+        check(fun, ret)?;
+        self.pause()
+    }
+
+    pub fn wheel_up(&self) -> Result<()> {
+        check("WheelUp", self.WheelUp()?)?;
+        self.pause()
+    }
+
+    pub fn wheel_down(&self) -> Result<()> {
+        check("WheelDown", self.WheelDown()?)?;
+        self.pause()
+    }
+
+    /// 移动到绑定窗口客户区坐标，未绑定时为屏幕坐标
+    pub fn move_to(&self, point: Point) -> Result<()> {
+        check("MoveTo", self.MoveTo(point.x, point.y)?)?;
+        self.pause()
+    }
+
+    /// 相对当前位置移动
+    pub fn move_by(&self, dx: i32, dy: i32) -> Result<()> {
+        check("MoveR", self.MoveR(dx, dy)?)?;
+        self.pause()
+    }
+
+    pub fn cursor_pos(&self) -> Result<Point> {
+        let (mut x, mut y) = (0, 0);
+        check("GetCursorPos", self.GetCursorPos(&mut x, &mut y)?)?;
+        Ok(Point::new(x, y))
+    }
+
+    /// 鼠标形状的特征码
+    pub fn cursor_shape(&self) -> Result<String> {
+        self.GetCursorShape()
+    }
+}
@@ -0,0 +1,161 @@
+//! 拟人的鼠标轨迹。
+//!
+//! 轨迹是一条三次贝塞尔曲线，两个控制点在起点到终点连线两侧随机偏移，
+//! 采样时使用先加速后减速的时间曲线，中间各点再加上少量抖动。
+//! 生成只依赖传入的 [`Rng`]，同一种子总是得到同样的轨迹。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::check;
+use crate::{AoJia, Clock, Point, Result, Rng, SystemClock};
+
+/// 轨迹中的一步，移动到 `point` 后等待 `delay`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathStep {
+    pub point: Point,
+    pub delay: Duration,
+}
+
+/// 轨迹的形状、速度和播放时使用的时钟
+#[derive(Clone)]
+pub struct Trajectory {
+    /// 控制点偏离连线的最大距离，占起点到终点距离的比例
+    pub curvature: f64,
+    /// 中间各点随机偏移的标准差，单位为像素
+    pub jitter: f64,
+    /// 平均每步移动的像素数
+    pub step_px: f64,
+    pub min_steps: usize,
+    pub max_steps: usize,
+    /// 平均速度，像素每秒
+    pub speed: f64,
+    pub clock: Arc<dyn Clock>,
+}
+
+impl std::fmt::Debug for Trajectory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Trajectory")
+            .field("curvature", &self.curvature)
+            .field("jitter", &self.jitter)
+            .field("step_px", &self.step_px)
+            .field("min_steps", &self.min_steps)
+            .field("max_steps", &self.max_steps)
+            .field("speed", &self.speed)
+            .finish()
+    }
+}
+
+impl Default for Trajectory {
+    fn default() -> Self {
+        Self {
+            curvature: 0.3,
+            jitter: 1.0,
+            step_px: 12.0,
+            min_steps: 8,
+            max_steps: 100,
+            speed: 1500.0,
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+/// 先加速后减速
+fn ease_in_out(t: f64) -> f64 {
+    (1.0 - (std::f64::consts::PI * t).cos()) / 2.0
+}
+
+fn bezier(p: [(f64, f64); 4], t: f64) -> (f64, f64) {
+    let u = 1.0 - t;
+    let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+    (
+        a * p[0].0 + b * p[1].0 + c * p[2].0 + d * p[3].0,
+        a * p[0].1 + b * p[1].1 + c * p[2].1 + d * p[3].1,
+    )
+}
+
+impl Trajectory {
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// 从 `from` 到 `to` 的轨迹，不含起点，最后一步一定是 `to`
+    pub fn path(&self, from: Point, to: Point, rng: &mut Rng) -> Vec<PathStep> {
+        let (dx, dy) = ((to.x - from.x) as f64, (to.y - from.y) as f64);
+        let distance = dx.hypot(dy);
+        if distance == 0.0 {
+            return vec![];
+        }
+        let steps = ((distance / self.step_px.max(1.0)).round() as usize)
+            .clamp(self.min_steps.max(1), self.max_steps.max(1));
+
+        // 连线的法向量，控制点沿它偏移
+        let (nx, ny) = (-dy / distance, dx / distance);
+        let control = |rng: &mut Rng, min: f64, max: f64| {
+            let t = rng.uniform(min, max);
+            let offset = rng.uniform(-1.0, 1.0) * self.curvature * distance;
+            (
+                from.x as f64 + dx * t + nx * offset,
+                from.y as f64 + dy * t + ny * offset,
+            )
+        };
+        let (c1, c2) = (control(rng, 0.2, 0.4), control(rng, 0.6, 0.8));
+        let curve = [
+            (from.x as f64, from.y as f64),
+            c1,
+            c2,
+            (to.x as f64, to.y as f64),
+        ];
+
+        let total = Duration::from_secs_f64(distance / self.speed.max(1.0));
+        let delay = total / steps as u32;
+        let mut path: Vec<PathStep> = Vec::with_capacity(steps);
+        // 没有移动的步骤把等待时间并入相邻的步骤
+        let mut pending = Duration::ZERO;
+        for i in 1..=steps {
+            let point = if i == steps {
+                to
+            } else {
+                let (x, y) = bezier(curve, ease_in_out(i as f64 / steps as f64));
+                Point::new(
+                    (x + rng.normal() * self.jitter).round() as i32,
+                    (y + rng.normal() * self.jitter).round() as i32,
+                )
+            };
+            match path.last_mut() {
+                Some(last) if last.point == point => last.delay += delay,
+                None if point == from => pending += delay,
+                _ => path.push(PathStep {
+                    point,
+                    delay: delay + std::mem::take(&mut pending),
+                }),
+            }
+        }
+        path
+    }
+}
+
+impl AoJia {
+    /// 依次用 MoveTo 移动到轨迹中的每个点
+    pub fn move_along(&self, path: &[PathStep], clock: &dyn Clock) -> Result<()> {
+        for step in path {
+            check("MoveTo", self.MoveTo(step.point.x, step.point.y)?)?;
+            clock.sleep(step.delay);
+        }
+        Ok(())
+    }
+
+    /// 沿随机生成的轨迹从 `from` 移动到 `to`，到达后按设置的延时等待一次
+    pub fn human_move(
+        &self,
+        from: Point,
+        to: Point,
+        trajectory: &Trajectory,
+        rng: &mut Rng,
+    ) -> Result<()> {
+        let path = trajectory.path(from, to, rng);
+        self.move_along(&path, trajectory.clock.as_ref())?;
+        self.pause()
+    }
+}
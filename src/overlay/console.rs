@@ -0,0 +1,230 @@
+//! 显示在覆盖窗口中的滚动日志。
+//!
+//! [`LogConsole`] 只保存文字，可以在任意线程写入，也可以用 [`LogConsole::install`]
+//! 设置为 `log` 的全局 logger；`tracing` 打开 `log` 特性后事件同样会转到这里。
+//! 插件对象不能跨线程，绘制由创建 [`Overlay`] 的线程定时调用 [`LogConsole::render`] 完成。
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use super::{Color, Font, Overlay};
+use crate::{Error, Result};
+
+/// 缓冲区中的一行日志
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleLine {
+    pub level: Level,
+    pub text: String,
+}
+
+#[derive(Debug)]
+struct Buffer {
+    lines: VecDeque<ConsoleLine>,
+    capacity: usize,
+    /// 末尾还没有绘制的行数
+    pending: usize,
+    colors: [Color; 5],
+}
+
+/// 保留最近 `capacity` 行的日志缓冲区，克隆得到的句柄共享同一份内容
+#[derive(Debug, Clone)]
+pub struct LogConsole {
+    buffer: Arc<Mutex<Buffer>>,
+}
+
+impl Default for LogConsole {
+    fn default() -> Self {
+        Self::new(200)
+    }
+}
+
+impl LogConsole {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: Arc::new(Mutex::new(Buffer {
+                lines: VecDeque::new(),
+                capacity: capacity.max(1),
+                pending: 0,
+                colors: [
+                    Color::RED,
+                    Color::YELLOW,
+                    Color::WHITE,
+                    Color::GRAY,
+                    Color::GRAY,
+                ],
+            })),
+        }
+    }
+
+    fn buffer(&self) -> MutexGuard<'_, Buffer> {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 设置某个级别的文字颜色
+    pub fn set_color(&self, level: Level, color: Color) {
+        self.buffer().colors[level as usize - 1] = color;
+    }
+
+    pub fn color(&self, level: Level) -> Color {
+        self.buffer().colors[level as usize - 1]
+    }
+
+    /// 添加日志，多行文字按行拆开，空文字作为一个空行，超出容量时丢弃最早的行
+    pub fn push(&self, level: Level, text: &str) {
+        let mut buffer = self.buffer();
+        // lines 对空字符串不产生任何行
+        for line in text.lines().chain(text.is_empty().then_some("")) {
+            buffer.lines.push_back(ConsoleLine {
+                level,
+                text: line.to_string(),
+            });
+            buffer.pending += 1;
+        }
+        let excess = buffer.lines.len().saturating_sub(buffer.capacity);
+        buffer.lines.drain(..excess);
+        buffer.pending = buffer.pending.min(buffer.lines.len());
+    }
+
+    pub fn lines(&self) -> Vec<ConsoleLine> {
+        self.buffer().lines.iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        let mut buffer = self.buffer();
+        buffer.lines.clear();
+        buffer.pending = 0;
+    }
+
+    /// 绘制上次之后新增的行，旧的行由文字区域自行滚动
+    pub fn render(&self, overlay: &Overlay<'_>) -> Result<()> {
+        let (lines, colors) = {
+            let mut buffer = self.buffer();
+            let start = buffer.lines.len() - buffer.pending;
+            let lines: Vec<ConsoleLine> = buffer.lines.range(start..).cloned().collect();
+            buffer.pending = 0;
+            (lines, buffer.colors)
+        };
+        draw(overlay, &lines, colors)
+    }
+
+    /// 清空文字区域，按行数重新绘制最近的日志
+    pub fn redraw(&self, overlay: &Overlay<'_>) -> Result<()> {
+        let (lines, colors) = {
+            let mut buffer = self.buffer();
+            buffer.pending = 0;
+            (
+                buffer.lines.iter().cloned().collect::<Vec<_>>(),
+                buffer.colors,
+            )
+        };
+        let area = overlay.text_area();
+        let wrapped = wrap_lines(&lines, overlay.font(), area.rect().width());
+        let rows = area.rows() as usize;
+        overlay.clear()?;
+        draw_wrapped(
+            overlay,
+            &wrapped[wrapped.len().saturating_sub(rows)..],
+            colors,
+        )
+    }
+
+    /// 写入级别不低于 `level` 的日志的 [`Log`] 实现
+    pub fn sink(&self, level: LevelFilter) -> ConsoleSink {
+        ConsoleSink {
+            console: self.clone(),
+            level,
+        }
+    }
+
+    /// 设置为全局 logger，进程中只能设置一次
+    pub fn install(&self, level: LevelFilter) -> Result<()> {
+        log::set_boxed_logger(Box::new(self.sink(level)))
+            .map_err(|e| Error::InvalidArgument(format!("设置全局 logger 失败: {}", e)))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+fn wrap_lines(lines: &[ConsoleLine], font: &Font, width: i32) -> Vec<ConsoleLine> {
+    lines
+        .iter()
+        .flat_map(|line| {
+            wrap(&line.text, font, width)
+                .into_iter()
+                .map(|text| ConsoleLine {
+                    level: line.level,
+                    text,
+                })
+        })
+        .collect()
+}
+
+fn draw_wrapped(overlay: &Overlay<'_>, lines: &[ConsoleLine], colors: [Color; 5]) -> Result<()> {
+    for line in lines {
+        overlay.print_colored(&line.text, colors[line.level as usize - 1])?;
+    }
+    Ok(())
+}
+
+fn draw(overlay: &Overlay<'_>, lines: &[ConsoleLine], colors: [Color; 5]) -> Result<()> {
+    let wrapped = wrap_lines(lines, overlay.font(), overlay.text_area().rect().width());
+    draw_wrapped(overlay, &wrapped, colors)
+}
+
+/// 按字体大小估算字符宽度：ASCII 字符按半个字号计算，其他字符按一个字号计算
+fn char_width(c: char, font: &Font) -> i32 {
+    if c.is_ascii() {
+        (font.size() + 1) / 2
+    } else {
+        font.size()
+    }
+}
+
+/// 估算一行文字的像素宽度
+pub(crate) fn text_width(text: &str, font: &Font) -> i32 {
+    text.chars().map(|c| char_width(c, font)).sum()
+}
+
+/// 把文字折成估算宽度不超过 `width` 像素的若干行，每行至少一个字符
+pub fn wrap(text: &str, font: &Font, width: i32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = char_width(c, font);
+        if !line.is_empty() && used + w > width {
+            lines.push(std::mem::take(&mut line));
+            used = 0;
+        }
+        line.push(c);
+        used += w;
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// 把 `log` 记录写入 [`LogConsole`]
+#[derive(Debug, Clone)]
+pub struct ConsoleSink {
+    console: LogConsole,
+    level: LevelFilter,
+}
+
+impl Log for ConsoleSink {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            self.console
+                .push(record.level(), &record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,369 @@
+//! 找图调试：把每次找图的区域和结果画到覆盖窗口上，过一段时间后消失，
+//! 也可以把同样的标注画到截图上保存下来。
+//!
+//! 绑定窗口后找图区域是客户区坐标，显示标注窗口时按绑定的窗口转换为屏幕坐标，
+//! 记录和保存的标注仍使用找图时的坐标。
+//! 标注窗口不会影响绑定窗口后的后台找图，前台模式找图时可能被截进画面。
+//! 等图时只标注最后一次找图的结果。
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::console::text_width;
+use super::{Color, Font, Overlay};
+use crate::pic::{Bitmap, PicMatch, PicSearch};
+use crate::window::WindowState;
+use crate::{AoJia, Clock, Hwnd, Point, Rect, Region, Result, SystemClock};
+
+/// 一次找图的区域和结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchAnnotation {
+    pub region: Rect,
+    pub pic_name: String,
+    pub matches: Vec<PicMatch>,
+}
+
+impl SearchAnnotation {
+    pub fn new(search: &PicSearch, matches: &[PicMatch]) -> Self {
+        Self {
+            region: search.region,
+            pic_name: search.pic_name.clone(),
+            matches: matches.to_vec(),
+        }
+    }
+
+    pub fn found(&self) -> bool {
+        !self.matches.is_empty()
+    }
+
+    /// 区域的说明文字
+    pub fn label(&self) -> String {
+        match self.matches.len() {
+            0 => format!("{} 未找到", self.pic_name),
+            n => format!("{} 找到 {} 处", self.pic_name, n),
+        }
+    }
+
+    /// 找到的位置的说明文字
+    pub fn match_label(m: &PicMatch) -> String {
+        format!("{} ({}, {})", m.name, m.x, m.y)
+    }
+}
+
+struct Shown {
+    until: Instant,
+    hwnds: Vec<Hwnd>,
+}
+
+/// 找图调试设置及已显示的标注，用 [`AoJia::set_pic_debug`] 打开
+pub struct PicDebug {
+    /// 标注显示的时间
+    pub fade: Duration,
+    /// 保留的最近找图次数，用于 [`PicDebug::dump`]
+    pub history: usize,
+    /// 区域边框和找到位置标记的粗细
+    pub border: i32,
+    pub font: Font,
+    pub found_color: Color,
+    pub missing_color: Color,
+    clock: Arc<dyn Clock>,
+    shown: Vec<Shown>,
+    annotations: VecDeque<SearchAnnotation>,
+}
+
+impl fmt::Debug for PicDebug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PicDebug")
+            .field("fade", &self.fade)
+            .field("history", &self.history)
+            .field("shown", &self.shown.len())
+            .field("annotations", &self.annotations.len())
+            .finish()
+    }
+}
+
+impl Default for PicDebug {
+    fn default() -> Self {
+        Self {
+            fade: Duration::from_secs(3),
+            history: 50,
+            border: 2,
+            font: Font::new("宋体", 12),
+            found_color: Color::GREEN,
+            missing_color: Color::RED,
+            clock: Arc::new(SystemClock),
+            shown: Vec::new(),
+            annotations: VecDeque::new(),
+        }
+    }
+}
+
+impl PicDebug {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fade(mut self, fade: Duration) -> Self {
+        self.fade = fade;
+        self
+    }
+
+    pub fn history(mut self, history: usize) -> Self {
+        self.history = history;
+        self
+    }
+
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    pub fn colors(mut self, found: Color, missing: Color) -> Self {
+        self.found_color = found;
+        self.missing_color = missing;
+        self
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// 最近的找图记录，从旧到新
+    pub fn annotations(&self) -> impl Iterator<Item = &SearchAnnotation> {
+        self.annotations.iter()
+    }
+
+    /// 当前显示的标注窗口
+    pub fn windows(&self) -> Vec<Hwnd> {
+        self.shown
+            .iter()
+            .flat_map(|s| s.hwnds.iter().copied())
+            .collect()
+    }
+
+    /// 记录一次找图并显示标注，显示前先关闭过期的标注。
+    /// 绑定了窗口时区域按客户区坐标转换到屏幕上显示
+    pub fn annotate(&mut self, aojia: &AoJia, annotation: SearchAnnotation) -> Result<()> {
+        self.expire(aojia);
+        let mut hwnds = Vec::new();
+        let ret = self.show(aojia, &annotation, &mut hwnds);
+        // 出错时已创建的窗口同样到期关闭
+        self.shown.push(Shown {
+            until: self.clock.now() + self.fade,
+            hwnds,
+        });
+        self.annotations.push_back(annotation);
+        let excess = self.annotations.len().saturating_sub(self.history);
+        self.annotations.drain(..excess);
+        ret
+    }
+
+    fn show(
+        &self,
+        aojia: &AoJia,
+        annotation: &SearchAnnotation,
+        hwnds: &mut Vec<Hwnd>,
+    ) -> Result<()> {
+        let color = if annotation.found() {
+            self.found_color
+        } else {
+            self.missing_color
+        };
+        let region = match aojia.binding() {
+            Some(binding) => Region::client(binding.hwnd, annotation.region),
+            None => Region::screen(annotation.region),
+        };
+        let region = aojia.to_screen(&region)?.rect;
+        let (dx, dy) = (
+            region.x1 - annotation.region.x1,
+            region.y1 - annotation.region.y1,
+        );
+        for edge in outline(region, self.border) {
+            hwnds.push(self.fill(aojia, edge, color)?);
+        }
+        let label = region.top_left().offset(self.border, self.border);
+        hwnds.push(self.label(aojia, label, &annotation.label(), color)?);
+        for m in &annotation.matches {
+            let point = Point::new(m.x + dx, m.y + dy);
+            hwnds.push(self.fill(aojia, marker(point, self.border), self.found_color)?);
+            let label = point.offset(self.border * 2, self.border * 2);
+            hwnds.push(self.label(
+                aojia,
+                label,
+                &SearchAnnotation::match_label(m),
+                self.found_color,
+            )?);
+        }
+        Ok(())
+    }
+
+    /// 用背景色填满的窗口
+    fn fill(&self, aojia: &AoJia, rect: Rect, color: Color) -> Result<Hwnd> {
+        let mut overlay = Overlay::new(aojia, rect)?;
+        overlay.set_color(color, color);
+        overlay.print(" ")?;
+        Ok(overlay.into_hwnd())
+    }
+
+    fn label(&self, aojia: &AoJia, at: Point, text: &str, color: Color) -> Result<Hwnd> {
+        let width = text_width(text, &self.font) + 4;
+        let rect = Rect::from_size(at.x, at.y, width, self.font.size() + 4);
+        let mut overlay = Overlay::new(aojia, rect)?;
+        overlay.set_font(self.font.clone())?;
+        overlay.set_color(color, Color::BLACK);
+        overlay.print(text)?;
+        Ok(overlay.into_hwnd())
+    }
+
+    /// 关闭显示时间已到的标注
+    pub fn expire(&mut self, aojia: &AoJia) {
+        let now = self.clock.now();
+        let (expired, shown) = std::mem::take(&mut self.shown)
+            .into_iter()
+            .partition(|s| s.until <= now);
+        self.shown = shown;
+        close(aojia, expired);
+    }
+
+    /// 关闭所有标注窗口，找图记录保留
+    pub fn close_all(&mut self, aojia: &AoJia) {
+        close(aojia, std::mem::take(&mut self.shown));
+    }
+
+    /// 把记录的标注画到图像上，区域画边框，找到的位置画十字
+    pub fn render(&self, canvas: &mut Bitmap) {
+        for annotation in &self.annotations {
+            let color = if annotation.found() {
+                self.found_color
+            } else {
+                self.missing_color
+            };
+            for edge in outline(annotation.region, self.border) {
+                fill_rect(canvas, edge, color);
+            }
+            for m in &annotation.matches {
+                let size = self.border * 3;
+                fill_rect(
+                    canvas,
+                    Rect::new(m.x - size, m.y, m.x + size, m.y),
+                    self.found_color,
+                );
+                fill_rect(
+                    canvas,
+                    Rect::new(m.x, m.y - size, m.x, m.y + size),
+                    self.found_color,
+                );
+            }
+        }
+    }
+
+    /// 把标注画到截图上按扩展名保存为 BMP 或 PNG，说明文字写入同名的 txt 文件。
+    /// 没有截图时画在刚好容纳所有区域的黑色背景上。
+    pub fn dump(&self, path: impl AsRef<Path>, screen: Option<&Bitmap>) -> Result<()> {
+        let path = path.as_ref();
+        let mut canvas = match screen {
+            Some(screen) => screen.clone(),
+            None => {
+                let (w, h) = self.annotations.iter().fold((1, 1), |(w, h), a| {
+                    (w.max(a.region.x2 + 1), h.max(a.region.y2 + 1))
+                });
+                Bitmap::new(w as u32, h as u32, Color::BLACK.0)
+            }
+        };
+        self.render(&mut canvas);
+        canvas.save(path)?;
+
+        let mut text = String::new();
+        for a in &self.annotations {
+            let Rect { x1, y1, x2, y2 } = a.region;
+            text += &format!("{},{},{},{} {}\n", x1, y1, x2, y2, a.label());
+            for m in &a.matches {
+                text += &format!("  {}\n", SearchAnnotation::match_label(m));
+            }
+        }
+        std::fs::write(path.with_extension("txt"), text)?;
+        Ok(())
+    }
+}
+
+/// 区域四条边，每条边为 `border` 像素宽的矩形
+fn outline(region: Rect, border: i32) -> Vec<Rect> {
+    let Rect { x1, y1, x2, y2 } = region;
+    let b = border.max(1) - 1;
+    vec![
+        Rect::new(x1, y1, x2, y1 + b),
+        Rect::new(x1, y2 - b, x2, y2),
+        Rect::new(x1, y1, x1 + b, y2),
+        Rect::new(x2 - b, y1, x2, y2),
+    ]
+}
+
+/// 以 `point` 为中心的方块标记
+fn marker(point: Point, border: i32) -> Rect {
+    let r = border.max(1);
+    Rect::new(point.x - r, point.y - r, point.x + r, point.y + r)
+}
+
+fn fill_rect(canvas: &mut Bitmap, rect: Rect, color: Color) {
+    let bounds = Rect::from_size(0, 0, canvas.width() as i32, canvas.height() as i32);
+    let Some(rect) = rect.intersect(&bounds) else {
+        return;
+    };
+    for y in rect.y1..=rect.y2 {
+        for x in rect.x1..=rect.x2 {
+            canvas.set_pixel(x as u32, y as u32, color.0);
+        }
+    }
+}
+
+fn close(aojia: &AoJia, shown: Vec<Shown>) {
+    for hwnd in shown.into_iter().flat_map(|s| s.hwnds) {
+        let _ = aojia.SetWindowState(hwnd, WindowState::Close.into());
+    }
+}
+
+impl AoJia {
+    /// 打开或关闭找图调试，关闭旧设置的标注窗口并返回旧设置
+    pub fn set_pic_debug(&self, debug: Option<PicDebug>) -> Option<PicDebug> {
+        let old = self.pic_debug.replace(debug);
+        old.map(|mut old| {
+            old.close_all(self);
+            old
+        })
+    }
+
+    /// 访问当前的找图调试设置，没有打开时返回 None。
+    ///
+    /// 调用 `f` 期间设置被取出，其中的找图不做标注；
+    /// 其中用 [`AoJia::set_pic_debug`] 设置的新值会保留，原设置的标注窗口被关闭。
+    pub fn pic_debug<R>(&self, f: impl FnOnce(&mut PicDebug) -> R) -> Option<R> {
+        let mut debug = self.pic_debug.borrow_mut().take()?;
+        let ret = f(&mut debug);
+        let mut slot = self.pic_debug.borrow_mut();
+        if slot.is_none() {
+            *slot = Some(debug);
+        } else {
+            drop(slot);
+            debug.close_all(self);
+        }
+        Some(ret)
+    }
+
+    /// 关闭到期的标注，没有新的找图时需要定时调用
+    pub fn fade_pic_debug(&self) {
+        self.pic_debug(|debug| debug.expire(self));
+    }
+
+    /// 找图后调用，标注失败只记录日志，不影响找图结果
+    pub(crate) fn annotate_search(&self, search: &PicSearch, matches: &[PicMatch]) {
+        let ret =
+            self.pic_debug(|debug| debug.annotate(self, SearchAnnotation::new(search, matches)));
+        if let Some(Err(e)) = ret {
+            log::warn!("找图调试标注失败: {}", e);
+        }
+    }
+}
@@ -0,0 +1,340 @@
+//! SetFont 和 SetTextD 的参数。
+//!
+//! [`Font`] 和 [`TextArea`] 在调用插件前由 `encode` 检查范围，
+//! 编码结果按顺序对应插件函数除窗口句柄外的参数。
+
+use std::ops::{BitOr, BitOrAssign};
+
+use crate::{Error, Rect, Result};
+
+/// 字体名最多的字符数，与 LOGFONT 的 LF_FACESIZE 一致
+pub const MAX_FAMILY_LEN: usize = 31;
+/// 字号的范围
+pub const SIZE_RANGE: std::ops::RangeInclusive<i32> = 1..=500;
+/// 文字区域行数的范围
+pub const ROWS_RANGE: std::ops::RangeInclusive<i32> = 1..=1000;
+
+/// SetFont 的 Weight 参数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FontWeight {
+    Thin = 100,
+    ExtraLight = 200,
+    Light = 300,
+    #[default]
+    Normal = 400,
+    Medium = 500,
+    SemiBold = 600,
+    Bold = 700,
+    ExtraBold = 800,
+    Black = 900,
+}
+
+impl From<FontWeight> for i32 {
+    fn from(weight: FontWeight) -> Self {
+        weight as i32
+    }
+}
+
+impl TryFrom<i32> for FontWeight {
+    type Error = Error;
+
+    fn try_from(weight: i32) -> Result<Self> {
+        match weight {
+            100 => Ok(Self::Thin),
+            200 => Ok(Self::ExtraLight),
+            300 => Ok(Self::Light),
+            400 => Ok(Self::Normal),
+            500 => Ok(Self::Medium),
+            600 => Ok(Self::SemiBold),
+            700 => Ok(Self::Bold),
+            800 => Ok(Self::ExtraBold),
+            900 => Ok(Self::Black),
+            _ => Err(Error::InvalidArgument(format!(
+                "Weight {} 不是 100 ~ 900 之间 100 的倍数",
+                weight
+            ))),
+        }
+    }
+}
+
+/// SetFont 的 Italic、Underline 和 StrikeOut 参数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FontStyle(u8);
+
+impl FontStyle {
+    pub const NONE: FontStyle = FontStyle(0);
+    pub const ITALIC: FontStyle = FontStyle(1 << 0);
+    pub const UNDERLINE: FontStyle = FontStyle(1 << 1);
+    pub const STRIKE_OUT: FontStyle = FontStyle(1 << 2);
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, other: FontStyle) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for FontStyle {
+    type Output = FontStyle;
+
+    fn bitor(self, rhs: FontStyle) -> FontStyle {
+        FontStyle(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for FontStyle {
+    fn bitor_assign(&mut self, rhs: FontStyle) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// 按顺序对应 SetFont 除 Hwnd 外的 6 个参数
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FontArgs {
+    pub name: String,
+    pub size: i32,
+    pub weight: i32,
+    pub italic: i32,
+    pub underline: i32,
+    pub strike_out: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Font {
+    family: String,
+    size: i32,
+    weight: FontWeight,
+    style: FontStyle,
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self::new("宋体", 12)
+    }
+}
+
+impl Font {
+    pub fn new(family: &str, size: i32) -> Self {
+        Self {
+            family: family.to_string(),
+            size,
+            weight: FontWeight::Normal,
+            style: FontStyle::NONE,
+        }
+    }
+
+    pub fn family(&self) -> &str {
+        &self.family
+    }
+
+    pub fn size(&self) -> i32 {
+        self.size
+    }
+
+    pub fn weight(&self) -> FontWeight {
+        self.weight
+    }
+
+    pub fn style(&self) -> FontStyle {
+        self.style
+    }
+
+    pub fn with_family(mut self, family: &str) -> Self {
+        self.family = family.to_string();
+        self
+    }
+
+    pub fn with_size(mut self, size: i32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn bold(self) -> Self {
+        self.with_weight(FontWeight::Bold)
+    }
+
+    /// 替换全部样式
+    pub fn with_style(mut self, style: FontStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.style |= FontStyle::ITALIC;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.style |= FontStyle::UNDERLINE;
+        self
+    }
+
+    pub fn strike_out(mut self) -> Self {
+        self.style |= FontStyle::STRIKE_OUT;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let family = self.family.trim();
+        if family.is_empty() {
+            return Err(Error::InvalidArgument("字体名为空".to_string()));
+        }
+        if family.chars().count() > MAX_FAMILY_LEN {
+            return Err(Error::InvalidArgument(format!(
+                "字体名 \"{}\" 超过 {} 个字符",
+                family, MAX_FAMILY_LEN
+            )));
+        }
+        if !SIZE_RANGE.contains(&self.size) {
+            return Err(Error::InvalidArgument(format!(
+                "字号 {} 不在 {} ~ {} 之间",
+                self.size,
+                SIZE_RANGE.start(),
+                SIZE_RANGE.end()
+            )));
+        }
+        Ok(())
+    }
+
+    /// 检查后编码为 SetFont 的参数
+    pub fn encode(&self) -> Result<FontArgs> {
+        self.validate()?;
+        let flag = |style| self.style.contains(style) as i32;
+        Ok(FontArgs {
+            name: self.family.trim().to_string(),
+            size: self.size,
+            weight: self.weight.into(),
+            italic: flag(FontStyle::ITALIC),
+            underline: flag(FontStyle::UNDERLINE),
+            strike_out: flag(FontStyle::STRIKE_OUT),
+        })
+    }
+}
+
+/// SetTextD 的 Dir 参数，新输出的一行出现的位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// 新行在下方，旧的行向上滚动
+    #[default]
+    Down = 0,
+    /// 新行在上方，旧的行向下滚动
+    Up = 1,
+}
+
+impl From<TextDirection> for i32 {
+    fn from(dir: TextDirection) -> Self {
+        dir as i32
+    }
+}
+
+impl TryFrom<i32> for TextDirection {
+    type Error = Error;
+
+    fn try_from(dir: i32) -> Result<Self> {
+        match dir {
+            0 => Ok(Self::Down),
+            1 => Ok(Self::Up),
+            _ => Err(Error::InvalidArgument(format!("Dir {} 不是 0 或 1", dir))),
+        }
+    }
+}
+
+/// 按顺序对应 SetTextD 除 Hwnd 外的 6 个参数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextAreaArgs {
+    pub x1: i32,
+    pub y1: i32,
+    pub x2: i32,
+    pub y2: i32,
+    pub row: i32,
+    pub dir: i32,
+}
+
+/// SetTextD 设置的文字区域，坐标相对于窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextArea {
+    rect: Rect,
+    rows: i32,
+    direction: TextDirection,
+}
+
+impl TextArea {
+    pub fn new(rect: impl Into<Rect>, rows: i32) -> Self {
+        Self {
+            rect: rect.into(),
+            rows,
+            direction: TextDirection::Down,
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// 最多显示的行数
+    pub fn rows(&self) -> i32 {
+        self.rows
+    }
+
+    pub fn direction(&self) -> TextDirection {
+        self.direction
+    }
+
+    pub fn with_rect(mut self, rect: impl Into<Rect>) -> Self {
+        self.rect = rect.into();
+        self
+    }
+
+    pub fn with_rows(mut self, rows: i32) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: TextDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.rect.is_empty() || self.rect.x1 < 0 || self.rect.y1 < 0 {
+            return Err(Error::InvalidArgument(format!(
+                "文字区域 {:?} 为空或超出窗口",
+                self.rect
+            )));
+        }
+        if !ROWS_RANGE.contains(&self.rows) {
+            return Err(Error::InvalidArgument(format!(
+                "行数 {} 不在 {} ~ {} 之间",
+                self.rows,
+                ROWS_RANGE.start(),
+                ROWS_RANGE.end()
+            )));
+        }
+        Ok(())
+    }
+
+    /// 检查后编码为 SetTextD 的参数
+    pub fn encode(&self) -> Result<TextAreaArgs> {
+        self.validate()?;
+        let Rect { x1, y1, x2, y2 } = self.rect;
+        Ok(TextAreaArgs {
+            x1,
+            y1,
+            x2,
+            y2,
+            row: self.rows,
+            dir: self.direction.into(),
+        })
+    }
+}
@@ -0,0 +1,243 @@
+//! 用 CreateWindows 创建的覆盖窗口，用于在游戏窗口上方显示文字。
+
+mod console;
+mod debug;
+mod font;
+
+use std::fmt;
+use std::str::FromStr;
+
+pub use console::{ConsoleLine, ConsoleSink, LogConsole, wrap};
+pub use debug::{PicDebug, SearchAnnotation};
+pub use font::{Font, FontArgs, FontStyle, FontWeight, TextArea, TextAreaArgs, TextDirection};
+
+use crate::error::check;
+use crate::window::WindowState;
+use crate::{AoJia, Error, Hwnd, Rect, Result};
+
+/// 插件使用的 `RRGGBB` 颜色
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Color(pub u32);
+
+impl Color {
+    pub const BLACK: Color = Color(0x000000);
+    pub const WHITE: Color = Color(0xFFFFFF);
+    pub const RED: Color = Color(0xFF0000);
+    pub const GREEN: Color = Color(0x00FF00);
+    pub const BLUE: Color = Color(0x0000FF);
+    pub const YELLOW: Color = Color(0xFFFF00);
+    pub const GRAY: Color = Color(0x808080);
+
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self(((r as u32) << 16) | ((g as u32) << 8) | b as u32)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:06X}", self.0 & 0xFFFFFF)
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.len() != 6 {
+            return Err(Error::InvalidArgument(format!(
+                "颜色 \"{}\" 不是 RRGGBB",
+                s
+            )));
+        }
+        u32::from_str_radix(s, 16)
+            .map(Color)
+            .map_err(|_| Error::InvalidArgument(format!("颜色 \"{}\" 不是 RRGGBB", s)))
+    }
+}
+
+/// CreateWindows 的其他参数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct OverlayOptions {
+    /// 圆角的宽和高，为 0 时是直角
+    pub corner: (i32, i32),
+    /// CreateWindows 的 Type 参数
+    pub ty: i32,
+}
+
+/// 拥有一个覆盖窗口，drop 时关闭
+#[derive(Debug)]
+pub struct Overlay<'a> {
+    aojia: &'a AoJia,
+    hwnd: Hwnd,
+    rect: Rect,
+    font: Font,
+    area: TextArea,
+    color: Color,
+    background: Color,
+    /// 窗口已关闭或已交出句柄，drop 时不再关闭
+    closed: bool,
+}
+
+impl<'a> Overlay<'a> {
+    /// 在屏幕区域 `rect` 创建窗口，文字区域默认为整个窗口
+    pub fn new(aojia: &'a AoJia, rect: Rect) -> Result<Self> {
+        Self::with_options(aojia, rect, OverlayOptions::default())
+    }
+
+    pub fn with_options(aojia: &'a AoJia, rect: Rect, options: OverlayOptions) -> Result<Self> {
+        if rect.is_empty() {
+            return Err(Error::InvalidArgument(format!("窗口区域 {:?} 为空", rect)));
+        }
+        let hwnd = aojia.CreateWindows(
+            rect.x1,
+            rect.y1,
+            rect.width(),
+            rect.height(),
+            options.corner.0,
+            options.corner.1,
+            options.ty,
+        )?;
+        check("CreateWindows", hwnd)?;
+        let overlay = Self {
+            aojia,
+            hwnd,
+            rect,
+            font: Font::default(),
+            area: TextArea::new(Rect::from_size(0, 0, rect.width(), rect.height()), 1),
+            color: Color::WHITE,
+            background: Color::BLACK,
+            closed: false,
+        };
+        // 创建失败时 drop 会关闭窗口
+        overlay.apply_font(&overlay.font.encode()?)?;
+        overlay.apply_text_area(&overlay.area.encode()?)?;
+        Ok(overlay)
+    }
+
+    pub fn hwnd(&self) -> Hwnd {
+        self.hwnd
+    }
+
+    /// 窗口的屏幕区域
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn text_area(&self) -> &TextArea {
+        &self.area
+    }
+
+    pub fn color(&self) -> (Color, Color) {
+        (self.color, self.background)
+    }
+
+    fn apply_font(&self, args: &FontArgs) -> Result<()> {
+        let ret = self.aojia.SetFont(
+            self.hwnd,
+            &args.name,
+            args.size,
+            args.weight,
+            args.italic,
+            args.underline,
+            args.strike_out,
+        )?;
+        check("SetFont", ret)
+    }
+
+    fn apply_text_area(&self, args: &TextAreaArgs) -> Result<()> {
+        let TextAreaArgs {
+            x1,
+            y1,
+            x2,
+            y2,
+            row,
+            dir,
+        } = *args;
+        let ret = self.aojia.SetTextD(self.hwnd, x1, y1, x2, y2, row, dir)?;
+        check("SetTextD", ret)
+    }
+
+    /// 检查并设置字体，失败时保留原来的字体
+    pub fn set_font(&mut self, font: Font) -> Result<()> {
+        self.apply_font(&font.encode()?)?;
+        self.font = font;
+        Ok(())
+    }
+
+    /// 检查并设置文字区域，失败时保留原来的区域
+    pub fn set_text_area(&mut self, area: TextArea) -> Result<()> {
+        self.apply_text_area(&area.encode()?)?;
+        self.area = area;
+        Ok(())
+    }
+
+    /// 同时设置字体和文字区域。两者都检查通过后才调用插件，
+    /// 设置文字区域失败时恢复原来的字体
+    pub fn set_style(&mut self, font: Font, area: TextArea) -> Result<()> {
+        let (font_args, area_args) = (font.encode()?, area.encode()?);
+        self.apply_font(&font_args)?;
+        if let Err(e) = self.apply_text_area(&area_args) {
+            let _ = self.apply_font(&self.font.encode()?);
+            return Err(e);
+        }
+        self.font = font;
+        self.area = area;
+        Ok(())
+    }
+
+    /// 设置之后 `print` 使用的文字颜色和背景色
+    pub fn set_color(&mut self, color: Color, background: Color) {
+        self.color = color;
+        self.background = background;
+    }
+
+    /// 在文字区域中输出一行
+    pub fn print(&self, text: &str) -> Result<()> {
+        self.print_colored(text, self.color)
+    }
+
+    pub fn print_colored(&self, text: &str, color: Color) -> Result<()> {
+        let ret = self.aojia.DrawTextD(
+            self.hwnd,
+            text,
+            &color.to_string(),
+            &self.background.to_string(),
+        )?;
+        check("DrawTextD", ret)
+    }
+
+    /// 重新设置文字区域，清除已输出的文字
+    pub fn clear(&self) -> Result<()> {
+        self.apply_text_area(&self.area.encode()?)
+    }
+
+    /// 交出窗口句柄，之后由调用者负责关闭窗口
+    pub fn into_hwnd(mut self) -> Hwnd {
+        self.closed = true;
+        self.hwnd
+    }
+
+    /// 关闭窗口并返回结果
+    pub fn close(mut self) -> Result<()> {
+        self.closed = true;
+        let ret = self
+            .aojia
+            .SetWindowState(self.hwnd, WindowState::Close.into())?;
+        check("SetWindowState", ret)
+    }
+}
+
+impl Drop for Overlay<'_> {
+    fn drop(&mut self) {
+        if !self.closed {
+            let _ = self
+                .aojia
+                .SetWindowState(self.hwnd, WindowState::Close.into());
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use std::path::Path;
+
+use crate::{Error, Result};
+
+/// 24/32 位 BMP 图像，像素按行从上到下存储，颜色格式为 0xRRGGBB
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    width: u32,
+    height: u32,
+    pixels: Vec<u32>,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| Error::Image("文件头不完整".to_string()))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| Error::Image("文件头不完整".to_string()))
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+impl Bitmap {
+    pub fn new(width: u32, height: u32, color: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color & 0xFFFFFF; width as usize * height as usize],
+        }
+    }
+
+    pub fn from_pixels(width: u32, height: u32, pixels: Vec<u32>) -> Result<Self> {
+        if (width as usize).checked_mul(height as usize) != Some(pixels.len()) {
+            return Err(Error::Image(format!(
+                "像素数量 {} 与尺寸 {}x{} 不符",
+                pixels.len(),
+                width,
+                height
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels: pixels.into_iter().map(|c| c & 0xFFFFFF).collect(),
+        })
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        Self::decode(&data).map_err(|e| match e {
+            Error::Image(msg) => Error::Image(format!("{}: {}", path.display(), msg)),
+            e => e,
+        })
+    }
+
+    /// 解析 BMP 文件内容，支持未压缩的 24/32 位图及 32 位 BI_BITFIELDS
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.get(0..2) != Some(b"BM") {
+            return Err(Error::Image("不是 BMP 文件".to_string()));
+        }
+        let offset = read_u32(data, 10)? as usize;
+        let header_size = read_u32(data, 14)?;
+        if header_size < 40 {
+            return Err(Error::Image(format!("不支持的信息头大小 {}", header_size)));
+        }
+        let width = read_u32(data, 18)? as i32;
+        let height = read_u32(data, 22)? as i32;
+        let bpp = read_u16(data, 28)?;
+        let compression = read_u32(data, 30)?;
+        if width <= 0 || height == 0 {
+            return Err(Error::Image(format!(
+                "图像尺寸 {}x{} 不合法",
+                width, height
+            )));
+        }
+
+        // BI_RGB 为固定的 BGR(A) 排列，BI_BITFIELDS 的掩码紧跟在 40 字节信息头之后
+        let masks = match (compression, bpp) {
+            (0, 24) | (0, 32) => [0xFF0000, 0x00FF00, 0x0000FF],
+            (3, 32) | (6, 32) => [
+                read_u32(data, 54)?,
+                read_u32(data, 58)?,
+                read_u32(data, 62)?,
+            ],
+            _ => {
+                return Err(Error::Image(format!(
+                    "不支持的格式: {} 位, 压缩方式 {}",
+                    bpp, compression
+                )));
+            }
+        };
+
+        let (width, top_down) = (width as u32, height < 0);
+        let height = height.unsigned_abs();
+        let bytes_pp = bpp as usize / 8;
+        // 尺寸来自文件头，计算大小时防止溢出
+        let too_large = || Error::Image(format!("图像尺寸 {}x{} 过大", width, height));
+        let stride = (width as usize * bpp as usize).div_ceil(32) * 4;
+        let size = stride
+            .checked_mul(height as usize)
+            .and_then(|size| size.checked_add(offset))
+            .ok_or_else(too_large)?;
+        let count = (width as usize)
+            .checked_mul(height as usize)
+            .filter(|&count| count <= u32::MAX as usize)
+            .ok_or_else(too_large)?;
+        if data.len() < size {
+            return Err(Error::Image("像素数据不完整".to_string()));
+        }
+
+        let channel = |px: u32, mask: u32| -> u32 {
+            if mask == 0 {
+                0
+            } else {
+                let v = (px & mask) >> mask.trailing_zeros();
+                let bits = (mask >> mask.trailing_zeros()).count_ones();
+                if bits >= 8 {
+                    v >> (bits - 8)
+                } else {
+                    v * 255 / ((1 << bits) - 1)
+                }
+            }
+        };
+
+        let mut pixels = Vec::with_capacity(count);
+        for y in 0..height {
+            let row = if top_down { y } else { height - 1 - y };
+            let start = offset + row as usize * stride;
+            for x in 0..width as usize {
+                let p = &data[start + x * bytes_pp..start + (x + 1) * bytes_pp];
+                let px = u32::from_le_bytes([p[0], p[1], p[2], *p.get(3).unwrap_or(&0)]);
+                let (r, g, b) = (
+                    channel(px, masks[0]),
+                    channel(px, masks[1]),
+                    channel(px, masks[2]),
+                );
+                pixels.push((r << 16) | (g << 8) | b);
+            }
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// 编码为自下而上存储的 24 位 BMP
+    pub fn encode(&self) -> Vec<u8> {
+        let stride = (self.width as usize * 3).div_ceil(4) * 4;
+        let size = 54 + stride * self.height as usize;
+        let mut data = Vec::with_capacity(size);
+        data.extend_from_slice(b"BM");
+        data.extend_from_slice(&(size as u32).to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&54u32.to_le_bytes());
+        data.extend_from_slice(&40u32.to_le_bytes());
+        data.extend_from_slice(&(self.width as i32).to_le_bytes());
+        data.extend_from_slice(&(self.height as i32).to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&24u16.to_le_bytes());
+        data.extend_from_slice(&[0; 24]);
+        for y in (0..self.height).rev() {
+            let start = data.len();
+            for x in 0..self.width {
+                let [b, g, r, _] = self.pixel(x, y).to_le_bytes();
+                data.extend_from_slice(&[b, g, r]);
+            }
+            data.resize(start + stride, 0);
+        }
+        data
+    }
+
+    /// 编码为 8 位 RGB 的 PNG，数据不压缩
+    pub fn encode_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width as usize * 3 + 1) * self.height as usize);
+        for y in 0..self.height {
+            raw.push(0);
+            for x in 0..self.width {
+                let [b, g, r, _] = self.pixel(x, y).to_le_bytes();
+                raw.extend_from_slice(&[r, g, b]);
+            }
+        }
+
+        // zlib 包装的 deflate 不压缩块，每块最多 65535 字节
+        let mut zlib = vec![0x78, 0x01];
+        let mut blocks = raw.chunks(0xFFFF).peekable();
+        if blocks.peek().is_none() {
+            zlib.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+        }
+        while let Some(block) = blocks.next() {
+            let len = block.len() as u16;
+            zlib.push(blocks.peek().is_none() as u8);
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&self.width.to_be_bytes());
+        ihdr.extend_from_slice(&self.height.to_be_bytes());
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+        for (kind, body) in [
+            (b"IHDR", &ihdr[..]),
+            (b"IDAT", &zlib[..]),
+            (b"IEND", &[][..]),
+        ] {
+            data.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            let start = data.len();
+            data.extend_from_slice(kind);
+            data.extend_from_slice(body);
+            let crc = crc32(&data[start..]);
+            data.extend_from_slice(&crc.to_be_bytes());
+        }
+        data
+    }
+
+    /// 按扩展名保存为 BMP 或 PNG
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        let data = match ext.as_deref() {
+            Some("bmp") => self.encode(),
+            Some("png") => self.encode_png(),
+            _ => {
+                return Err(Error::Image(format!(
+                    "{}: 只支持保存为 bmp 或 png",
+                    path.display()
+                )));
+            }
+        };
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> u32 {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: u32) {
+        self.pixels[(y * self.width + x) as usize] = color & 0xFFFFFF;
+    }
+
+    /// 截取一块区域，超出图像的部分会被裁掉
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Bitmap {
+        let x2 = (x + width).min(self.width);
+        let y2 = (y + height).min(self.height);
+        let (x, y) = (x.min(x2), y.min(y2));
+        let mut pixels = Vec::with_capacity(((x2 - x) * (y2 - y)) as usize);
+        for row in y..y2 {
+            let start = (row * self.width) as usize;
+            pixels.extend_from_slice(&self.pixels[start + x as usize..start + x2 as usize]);
+        }
+        Bitmap {
+            width: x2 - x,
+            height: y2 - y,
+            pixels,
+        }
+    }
+
+    /// 把另一张图贴到 (x, y) 处，超出的部分会被裁掉
+    pub fn paste(&mut self, x: u32, y: u32, other: &Bitmap) {
+        for row in 0..other.height.min(self.height.saturating_sub(y)) {
+            for col in 0..other.width.min(self.width.saturating_sub(x)) {
+                self.set_pixel(x + col, y + row, other.pixel(col, row));
+            }
+        }
+    }
+}
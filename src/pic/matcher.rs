@@ -0,0 +1,245 @@
+//! FindPic 的纯 Rust 参考实现，模拟后端用它在截图上找图，
+//! 也可以单独拿来对着截图调试相似度。
+//!
+//! 与插件一致的约定：
+//! - 区域 `x1, y1, x2, y2` 包含右下角，超出屏幕的部分会被裁掉，图片必须完整落在区域内
+//! - `ColorP` 为透明色，格式 `RRGGBB`，多个用 `|` 分隔，图片中这些颜色的像素不参与比较
+//! - `Sim` 为非透明像素中颜色完全相同的比例，取值 0.0 ~ 1.0
+//! - `Dir` 0: 从左到右从上到下，1: 从左到右从下到上，2: 从右到左从上到下，3: 从右到左从下到上
+//! - `Type` 0: 按图片顺序逐张查找，1: 按位置顺序查找，同一位置按图片顺序比较
+//! - 找到后返回图片序号、图片名和左上角坐标
+
+use std::path::Path;
+use std::rc::Rc;
+
+use super::{Bitmap, FindPicMode, PicMatch, SearchDirection, Similarity};
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matcher {
+    transparent: Vec<u32>,
+    sim: Similarity,
+    dir: SearchDirection,
+    mode: FindPicMode,
+}
+
+/// 把 ColorP 解析为透明色列表
+pub fn parse_color_p(color_p: &str) -> Result<Vec<u32>> {
+    color_p
+        .split('|')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            if s.len() != 6 {
+                return Err(Error::InvalidArgument(format!(
+                    "颜色 {} 不是 RRGGBB 格式",
+                    s
+                )));
+            }
+            u32::from_str_radix(s, 16)
+                .map_err(|_| Error::InvalidArgument(format!("颜色 {} 不是 RRGGBB 格式", s)))
+        })
+        .collect()
+}
+
+/// 按 PicName 从目录中加载图片，多个图片用 `|` 分隔
+pub fn load_pics(dir: impl AsRef<Path>, pic_name: &str) -> Result<Vec<(String, Bitmap)>> {
+    pic_name
+        .split('|')
+        .filter(|name| !name.is_empty())
+        .map(|name| Ok((name.to_string(), Bitmap::open(dir.as_ref().join(name))?)))
+        .collect()
+}
+
+impl Matcher {
+    pub fn new(
+        color_p: &str,
+        sim: Similarity,
+        dir: SearchDirection,
+        mode: FindPicMode,
+    ) -> Result<Self> {
+        Ok(Self {
+            transparent: parse_color_p(color_p)?,
+            sim,
+            dir,
+            mode,
+        })
+    }
+
+    /// 图片放在 (x, y) 处时与屏幕的相似度，超出屏幕或没有非透明像素时为 0
+    pub fn score(&self, screen: &Bitmap, pic: &Bitmap, x: i32, y: i32) -> f64 {
+        self.compare(screen, pic, &self.opaque(pic), x, y, 0.0)
+            .unwrap_or(0.0)
+    }
+
+    /// 图片中非透明像素的坐标和颜色，每张图片只需计算一次
+    fn opaque(&self, pic: &Bitmap) -> Vec<(u32, u32, u32)> {
+        (0..pic.height())
+            .flat_map(|py| (0..pic.width()).map(move |px| (px, py, pic.pixel(px, py))))
+            .filter(|(_, _, color)| !self.transparent.contains(color))
+            .collect()
+    }
+
+    /// 比较图片与屏幕，`opaque` 为 [`Matcher::opaque`] 的结果，相似度低于 `sim` 时提前返回 None
+    fn compare(
+        &self,
+        screen: &Bitmap,
+        pic: &Bitmap,
+        opaque: &[(u32, u32, u32)],
+        x: i32,
+        y: i32,
+        sim: f64,
+    ) -> Option<f64> {
+        if opaque.is_empty()
+            || x < 0
+            || y < 0
+            || x as u32 + pic.width() > screen.width()
+            || y as u32 + pic.height() > screen.height()
+        {
+            return None;
+        }
+
+        let total = opaque.len();
+        let allowed = ((1.0 - sim) * total as f64 + 1e-9).floor().max(0.0) as usize;
+        let mut missed = 0;
+        for &(px, py, color) in opaque {
+            if screen.pixel(x as u32 + px, y as u32 + py) != color {
+                missed += 1;
+                if missed > allowed {
+                    return None;
+                }
+            }
+        }
+        Some((total - missed) as f64 / total as f64)
+    }
+
+    /// 按 Dir 的顺序列出图片在区域内所有可能的左上角坐标
+    fn positions(
+        &self,
+        screen: &Bitmap,
+        (x1, y1, x2, y2): (i32, i32, i32, i32),
+        (width, height): (u32, u32),
+    ) -> Vec<(i32, i32)> {
+        let x1 = x1.max(0);
+        let y1 = y1.max(0);
+        let x2 = x2.min(screen.width() as i32 - 1) - width as i32 + 1;
+        let y2 = y2.min(screen.height() as i32 - 1) - height as i32 + 1;
+        if x1 > x2 || y1 > y2 {
+            return Vec::new();
+        }
+
+        let xs: Vec<i32> = match self.dir {
+            SearchDirection::LeftRightTopDown | SearchDirection::LeftRightBottomUp => {
+                (x1..=x2).collect()
+            }
+            _ => (x1..=x2).rev().collect(),
+        };
+        let ys: Vec<i32> = match self.dir {
+            SearchDirection::LeftRightTopDown | SearchDirection::RightLeftTopDown => {
+                (y1..=y2).collect()
+            }
+            _ => (y1..=y2).rev().collect(),
+        };
+        ys.iter()
+            .flat_map(|&y| xs.iter().map(move |&x| (x, y)))
+            .collect()
+    }
+
+    /// 按 Type 和 Dir 的顺序逐个产生找到的结果
+    fn matches<'a>(
+        &'a self,
+        screen: &'a Bitmap,
+        region: (i32, i32, i32, i32),
+        pics: &'a [(&'a str, &'a Bitmap)],
+    ) -> Box<dyn Iterator<Item = PicMatch> + 'a> {
+        let opaque: Rc<[Vec<(u32, u32, u32)>]> =
+            pics.iter().map(|(_, pic)| self.opaque(pic)).collect();
+        let hit = move |index: usize, opaque: &[(u32, u32, u32)], x: i32, y: i32| {
+            let pic = pics[index].1;
+            x + pic.width() as i32 <= region.2 + 1
+                && y + pic.height() as i32 <= region.3 + 1
+                && self
+                    .compare(screen, pic, opaque, x, y, self.sim.value())
+                    .is_some()
+        };
+        let found = move |index: usize, x: i32, y: i32| PicMatch {
+            index,
+            name: pics[index].0.to_string(),
+            x,
+            y,
+        };
+
+        if self.mode == FindPicMode::PicOrder {
+            Box::new(pics.iter().enumerate().flat_map(move |(index, (_, pic))| {
+                let opaque = opaque.clone();
+                self.positions(screen, region, (pic.width(), pic.height()))
+                    .into_iter()
+                    .filter(move |&(x, y)| hit(index, &opaque[index], x, y))
+                    .map(move |(x, y)| found(index, x, y))
+            }))
+        } else {
+            let (width, height) = pics.iter().fold((u32::MAX, u32::MAX), |(w, h), (_, pic)| {
+                (w.min(pic.width()), h.min(pic.height()))
+            });
+            Box::new(
+                self.positions(screen, region, (width, height))
+                    .into_iter()
+                    .flat_map(move |(x, y)| {
+                        let opaque = opaque.clone();
+                        (0..pics.len())
+                            .filter(move |&index| hit(index, &opaque[index], x, y))
+                            .map(move |index| found(index, x, y))
+                    }),
+            )
+        }
+    }
+
+    /// 在区域内查找，返回第一个找到的图片
+    pub fn find(
+        &self,
+        screen: &Bitmap,
+        region: (i32, i32, i32, i32),
+        pics: &[(&str, &Bitmap)],
+    ) -> Option<PicMatch> {
+        if pics.is_empty() {
+            return None;
+        }
+        self.matches(screen, region, pics).next()
+    }
+
+    /// 在区域内查找所有位置，对应 FindPicEx
+    pub fn find_all(
+        &self,
+        screen: &Bitmap,
+        region: (i32, i32, i32, i32),
+        pics: &[(&str, &Bitmap)],
+    ) -> Vec<PicMatch> {
+        if pics.is_empty() {
+            return Vec::new();
+        }
+        self.matches(screen, region, pics).collect()
+    }
+
+    /// 区域内相似度最高的位置，用于确定合适的 Sim
+    pub fn best(
+        &self,
+        screen: &Bitmap,
+        region: (i32, i32, i32, i32),
+        pic: &Bitmap,
+    ) -> Option<(i32, i32, f64)> {
+        let opaque = self.opaque(pic);
+        let mut best: Option<(i32, i32, f64)> = None;
+        for (x, y) in self.positions(screen, region, (pic.width(), pic.height())) {
+            // 以目前最高的相似度为下限，更差的位置提前结束比较
+            let floor = best.map_or(0.0, |b| b.2);
+            let score = self
+                .compare(screen, pic, &opaque, x, y, floor)
+                .unwrap_or(0.0);
+            match best {
+                Some(b) if b.2 >= score => {}
+                _ => best = Some((x, y, score)),
+            }
+        }
+        best
+    }
+}
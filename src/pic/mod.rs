@@ -0,0 +1,277 @@
+pub mod bmp;
+pub mod matcher;
+mod wait;
+
+pub use bmp::Bitmap;
+pub use matcher::Matcher;
+pub use wait::WaitOptions;
+
+use crate::{AoJia, Error, Rect, Result};
+
+/// 找图结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PicMatch {
+    /// 图片在 PicName 中的序号，从 0 开始
+    pub index: usize,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// FindPic 的 Dir 参数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SearchDirection {
+    /// 从左到右，从上到下
+    #[default]
+    LeftRightTopDown = 0,
+    /// 从左到右，从下到上
+    LeftRightBottomUp = 1,
+    /// 从右到左，从上到下
+    RightLeftTopDown = 2,
+    /// 从右到左，从下到上
+    RightLeftBottomUp = 3,
+}
+
+impl From<SearchDirection> for i32 {
+    fn from(dir: SearchDirection) -> Self {
+        dir as i32
+    }
+}
+
+impl TryFrom<i32> for SearchDirection {
+    type Error = Error;
+
+    fn try_from(dir: i32) -> Result<Self> {
+        match dir {
+            0 => Ok(Self::LeftRightTopDown),
+            1 => Ok(Self::LeftRightBottomUp),
+            2 => Ok(Self::RightLeftTopDown),
+            3 => Ok(Self::RightLeftBottomUp),
+            _ => Err(Error::InvalidArgument(format!(
+                "Dir {} 不在 0 ~ 3 之间",
+                dir
+            ))),
+        }
+    }
+}
+
+/// FindPic 的 Type 参数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FindPicMode {
+    /// 按图片顺序逐张查找，返回第一张找到的图片
+    #[default]
+    PicOrder = 0,
+    /// 按位置顺序查找，返回最先找到的位置，同一位置按图片顺序比较
+    PositionOrder = 1,
+}
+
+impl From<FindPicMode> for i32 {
+    fn from(mode: FindPicMode) -> Self {
+        mode as i32
+    }
+}
+
+impl TryFrom<i32> for FindPicMode {
+    type Error = Error;
+
+    fn try_from(mode: i32) -> Result<Self> {
+        match mode {
+            0 => Ok(Self::PicOrder),
+            1 => Ok(Self::PositionOrder),
+            _ => Err(Error::InvalidArgument(format!(
+                "Type {} 不在 0 ~ 1 之间",
+                mode
+            ))),
+        }
+    }
+}
+
+/// FindPic 的 Sim 参数，取值 0.0 ~ 1.0
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Similarity(f64);
+
+impl Similarity {
+    pub const EXACT: Similarity = Similarity(1.0);
+
+    pub fn new(sim: f64) -> Result<Self> {
+        if (0.0..=1.0).contains(&sim) {
+            Ok(Self(sim))
+        } else {
+            Err(Error::InvalidArgument(format!(
+                "Sim {} 不在 0.0 ~ 1.0 之间",
+                sim
+            )))
+        }
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl Default for Similarity {
+    fn default() -> Self {
+        Self(0.9)
+    }
+}
+
+impl TryFrom<f64> for Similarity {
+    type Error = Error;
+
+    fn try_from(sim: f64) -> Result<Self> {
+        Self::new(sim)
+    }
+}
+
+impl From<Similarity> for f64 {
+    fn from(sim: Similarity) -> Self {
+        sim.0
+    }
+}
+
+/// FindPic 的查找条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct PicSearch {
+    pub region: Rect,
+    /// 图片名，多个用 `|` 分隔
+    pub pic_name: String,
+    /// 透明色，多个用 `|` 分隔
+    pub color_p: String,
+    pub sim: Similarity,
+    pub dir: SearchDirection,
+    pub mode: FindPicMode,
+}
+
+impl PicSearch {
+    pub fn new(region: impl Into<Rect>, pic_name: &str) -> Self {
+        Self {
+            region: region.into(),
+            pic_name: pic_name.to_string(),
+            color_p: String::new(),
+            sim: Similarity::default(),
+            dir: SearchDirection::default(),
+            mode: FindPicMode::default(),
+        }
+    }
+
+    pub fn color_p(mut self, color_p: &str) -> Self {
+        self.color_p = color_p.to_string();
+        self
+    }
+
+    pub fn sim(mut self, sim: Similarity) -> Self {
+        self.sim = sim;
+        self
+    }
+
+    pub fn dir(mut self, dir: SearchDirection) -> Self {
+        self.dir = dir;
+        self
+    }
+
+    pub fn mode(mut self, mode: FindPicMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 在调用插件前检查区域、图片名和透明色
+    pub fn validate(&self) -> Result<()> {
+        if self.region.is_empty() {
+            return Err(Error::InvalidArgument(format!(
+                "区域 {:?} 为空",
+                self.region
+            )));
+        }
+        if self.pic_name.split('|').any(|name| name.trim().is_empty()) {
+            return Err(Error::InvalidArgument(format!(
+                "图片名 \"{}\" 中有空项",
+                self.pic_name
+            )));
+        }
+        matcher::parse_color_p(&self.color_p)?;
+        Ok(())
+    }
+}
+
+/// 解析 FindPicEx 的返回值，格式为 `序号,x,y|序号,x,y`，序号也可以是图片名
+pub fn parse_pic_matches(result: &str, pic_name: &str) -> Result<Vec<PicMatch>> {
+    let names: Vec<&str> = pic_name.split('|').collect();
+    result
+        .split('|')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let invalid = || Error::Convert(format!("无法解析找图结果 \"{}\"", item));
+            let fields: Vec<&str> = item.split(',').map(str::trim).collect();
+            let [pic, x, y] = fields[..] else {
+                return Err(invalid());
+            };
+            let index = match pic.parse::<usize>() {
+                Ok(index) => index,
+                Err(_) => names.iter().position(|n| *n == pic).ok_or_else(invalid)?,
+            };
+            Ok(PicMatch {
+                index,
+                name: names.get(index).ok_or_else(invalid)?.to_string(),
+                x: x.parse().map_err(|_| invalid())?,
+                y: y.parse().map_err(|_| invalid())?,
+            })
+        })
+        .collect()
+}
+
+impl AoJia {
+    /// 检查参数后调用 FindPic，没找到时返回 None
+    pub fn find_pic(&self, search: &PicSearch) -> Result<Option<PicMatch>> {
+        let found = self.find_pic_quiet(search)?;
+        self.annotate_search(search, found.as_slice());
+        Ok(found)
+    }
+
+    /// 不做找图调试标注的 [`AoJia::find_pic`]，供轮询时使用
+    pub(crate) fn find_pic_quiet(&self, search: &PicSearch) -> Result<Option<PicMatch>> {
+        search.validate()?;
+        let Rect { x1, y1, x2, y2 } = search.region;
+        let (mut pic, mut x, mut y) = (String::new(), -1, -1);
+        let ret = self.FindPic(
+            x1,
+            y1,
+            x2,
+            y2,
+            &search.pic_name,
+            &search.color_p,
+            search.sim.into(),
+            search.dir.into(),
+            search.mode.into(),
+            &mut pic,
+            &mut x,
+            &mut y,
+        )?;
+        Ok((ret >= 0).then_some(PicMatch {
+            index: ret as usize,
+            name: pic,
+            x,
+            y,
+        }))
+    }
+
+    /// 调用 FindPicEx 返回所有找到的位置
+    pub fn find_pic_all(&self, search: &PicSearch) -> Result<Vec<PicMatch>> {
+        search.validate()?;
+        let Rect { x1, y1, x2, y2 } = search.region;
+        let ret = self.FindPicEx(
+            x1,
+            y1,
+            x2,
+            y2,
+            &search.pic_name,
+            &search.color_p,
+            search.sim.into(),
+            search.dir.into(),
+            search.mode.into(),
+        )?;
+        let found = parse_pic_matches(&ret, &search.pic_name)?;
+        self.annotate_search(search, &found);
+        Ok(found)
+    }
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{PicMatch, PicSearch};
+use crate::{AoJia, CancellationToken, Clock, Error, Result, SystemClock};
+
+/// 等图的超时、轮询间隔、时钟和取消
+#[derive(Clone)]
+pub struct WaitOptions {
+    pub timeout: Duration,
+    pub interval: Duration,
+    pub clock: Arc<dyn Clock>,
+    pub cancel: Option<CancellationToken>,
+}
+
+impl std::fmt::Debug for WaitOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WaitOptions")
+            .field("timeout", &self.timeout)
+            .field("interval", &self.interval)
+            .field("cancel", &self.cancel)
+            .finish()
+    }
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            interval: Duration::from_millis(200),
+            clock: Arc::new(SystemClock),
+            cancel: None,
+        }
+    }
+}
+
+impl WaitOptions {
+    pub fn new(timeout: Duration, interval: Duration) -> Self {
+        Self {
+            timeout,
+            interval,
+            ..Default::default()
+        }
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn cancel(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// 反复执行 `poll` 直到返回 Some、超时或被取消
+    pub fn poll<T>(&self, mut poll: impl FnMut() -> Result<Option<T>>) -> Result<T> {
+        let start = self.clock.now();
+        loop {
+            if self.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+                return Err(Error::Cancelled);
+            }
+            if let Some(value) = poll()? {
+                return Ok(value);
+            }
+            let elapsed = self.clock.now().duration_since(start);
+            if elapsed >= self.timeout {
+                return Err(Error::Timeout(self.timeout));
+            }
+            self.clock.sleep(self.interval.min(self.timeout - elapsed));
+        }
+    }
+}
+
+impl AoJia {
+    /// 轮询找图，结束后只为最后一次的结果做找图调试标注
+    fn poll_pic<T>(
+        &self,
+        search: &PicSearch,
+        options: &WaitOptions,
+        mut done: impl FnMut(&Option<PicMatch>) -> Option<T>,
+    ) -> Result<T> {
+        search.validate()?;
+        let mut last = None;
+        let ret = options.poll(|| {
+            let found = self.find_pic_quiet(search)?;
+            let ret = done(&found);
+            last = Some(found);
+            Ok(ret)
+        });
+        if let Some(found) = last {
+            self.annotate_search(search, found.as_slice());
+        }
+        ret
+    }
+
+    /// 等待图片出现，超时返回 [`Error::Timeout`]
+    pub fn wait_for_pic(&self, search: &PicSearch, options: &WaitOptions) -> Result<PicMatch> {
+        self.poll_pic(search, options, Option::clone)
+    }
+
+    /// 等待图片消失，超时返回 [`Error::Timeout`]
+    pub fn wait_until_gone(&self, search: &PicSearch, options: &WaitOptions) -> Result<()> {
+        self.poll_pic(search, options, |found| found.is_none().then_some(()))
+    }
+}
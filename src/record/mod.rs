@@ -0,0 +1,293 @@
+//! 操作宏的录制和回放。
+//!
+//! 宏文件每行一个步骤，空行和以 `#` 开头的行会被忽略：
+//!
+//! ```text
+//! # 打开背包
+//! move 100 200
+//! click left
+//! click right 300 400
+//! double left
+//! down left
+//! up left
+//! wheel up
+//! key ctrl+shift+F5
+//! type 要输入的文字
+//! wait 500
+//! wait 200-800
+//! expect bag.bmp 0 0 800 600 sim=0.95 dir=2 timeout=3000
+//! ```
+//!
+//! 坐标为整数，时间单位为毫秒。`click` 可以带坐标，先移动再点击。
+//! `type` 后面的一个空格是分隔符，之后直到行尾都是要输入的文字，首尾的空白也会保留，
+//! 文字中不能有换行。`wait a-b` 在 a 和 b 之间随机等待。
+//! `expect` 检查区域内能否找到图片，可选的 `sim`、`color`、`dir`、`mode` 和 `timeout` 写成 `键=值`，
+//! `dir` 和 `mode` 为 FindPic 的 Dir 和 Type 参数。图片名中不能有空格。保存时注释不会保留。
+
+mod player;
+mod recorder;
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub use player::{PlayReport, Player, StepMismatch};
+pub use recorder::Recorder;
+
+use crate::input::{Chord, MouseButton};
+use crate::pic::{FindPicMode, PicSearch, SearchDirection, Similarity};
+use crate::{Error, Point, Rect, Result};
+
+/// 宏中的一个步骤
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Move(Point),
+    /// 有坐标时先移动到该点
+    Click {
+        button: MouseButton,
+        at: Option<Point>,
+    },
+    DoubleClick(MouseButton),
+    Down(MouseButton),
+    Up(MouseButton),
+    WheelUp,
+    WheelDown,
+    Key(Chord),
+    Type(String),
+    /// 在 `min` 和 `max` 之间随机等待，两者相同时为固定时间
+    Wait {
+        min: Duration,
+        max: Duration,
+    },
+    /// 检查图片是否出现，`timeout` 为零时只找一次
+    Expect {
+        search: PicSearch,
+        timeout: Duration,
+    },
+}
+
+fn number<T: FromStr>(s: &str) -> std::result::Result<T, String> {
+    s.parse().map_err(|_| format!("\"{}\" 不是合法的数字", s))
+}
+
+fn millis(s: &str) -> std::result::Result<Duration, String> {
+    number(s).map(Duration::from_millis)
+}
+
+fn parse_step(line: &str) -> std::result::Result<Step, String> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let arity = |n: &[usize]| {
+        if n.contains(&(tokens.len() - 1)) {
+            Ok(())
+        } else {
+            Err(format!("{} 的参数个数不对", tokens[0]))
+        }
+    };
+    let button = |s: &str| s.parse::<MouseButton>().map_err(|e| e.to_string());
+    let point = |x: &str, y: &str| Ok::<_, String>(Point::new(number(x)?, number(y)?));
+    match tokens[0] {
+        "move" => {
+            arity(&[2])?;
+            Ok(Step::Move(point(tokens[1], tokens[2])?))
+        }
+        "click" => {
+            arity(&[1, 3])?;
+            let at = match tokens.len() {
+                4 => Some(point(tokens[2], tokens[3])?),
+                _ => None,
+            };
+            Ok(Step::Click {
+                button: button(tokens[1])?,
+                at,
+            })
+        }
+        "double" => {
+            arity(&[1])?;
+            Ok(Step::DoubleClick(button(tokens[1])?))
+        }
+        "down" => {
+            arity(&[1])?;
+            Ok(Step::Down(button(tokens[1])?))
+        }
+        "up" => {
+            arity(&[1])?;
+            Ok(Step::Up(button(tokens[1])?))
+        }
+        "wheel" => {
+            arity(&[1])?;
+            match tokens[1] {
+                "up" => Ok(Step::WheelUp),
+                "down" => Ok(Step::WheelDown),
+                other => Err(format!("滚轮方向 \"{}\" 不存在", other)),
+            }
+        }
+        "key" => {
+            arity(&[1])?;
+            Ok(Step::Key(
+                tokens[1].parse().map_err(|e: Error| e.to_string())?,
+            ))
+        }
+        "type" => {
+            // 只去掉作为分隔符的一个空白，其余的空白都是文字的一部分
+            let rest = &line.trim_start()["type".len()..];
+            let text = rest.strip_prefix([' ', '\t']).unwrap_or(rest);
+            if text.is_empty() {
+                return Err("type 缺少文字".to_string());
+            }
+            Ok(Step::Type(text.to_string()))
+        }
+        "wait" => {
+            arity(&[1])?;
+            let (min, max) = match tokens[1].split_once('-') {
+                Some((min, max)) => (millis(min)?, millis(max)?),
+                None => (millis(tokens[1])?, millis(tokens[1])?),
+            };
+            if min > max {
+                return Err("等待时间下限大于上限".to_string());
+            }
+            Ok(Step::Wait { min, max })
+        }
+        "expect" => {
+            if tokens.len() < 6 {
+                return Err("expect 的参数个数不对".to_string());
+            }
+            let region = Rect::new(
+                number(tokens[2])?,
+                number(tokens[3])?,
+                number(tokens[4])?,
+                number(tokens[5])?,
+            );
+            let mut search = PicSearch::new(region, tokens[1]);
+            let mut timeout = Duration::ZERO;
+            for option in &tokens[6..] {
+                match option.split_once('=') {
+                    Some(("sim", v)) => {
+                        search.sim = Similarity::new(number(v)?).map_err(|e| e.to_string())?
+                    }
+                    Some(("color", v)) => search.color_p = v.to_string(),
+                    Some(("dir", v)) => {
+                        search.dir = SearchDirection::try_from(number::<i32>(v)?)
+                            .map_err(|e| e.to_string())?
+                    }
+                    Some(("mode", v)) => {
+                        search.mode =
+                            FindPicMode::try_from(number::<i32>(v)?).map_err(|e| e.to_string())?
+                    }
+                    Some(("timeout", v)) => timeout = millis(v)?,
+                    _ => return Err(format!("expect 的选项 \"{}\" 不存在", option)),
+                }
+            }
+            search.validate().map_err(|e| e.to_string())?;
+            Ok(Step::Expect { search, timeout })
+        }
+        other => Err(format!("步骤 \"{}\" 不存在", other)),
+    }
+}
+
+impl FromStr for Step {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.trim().is_empty() {
+            return Err(Error::InvalidArgument("步骤为空".to_string()));
+        }
+        parse_step(s).map_err(Error::InvalidArgument)
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Move(p) => write!(f, "move {} {}", p.x, p.y),
+            Step::Click { button, at: None } => write!(f, "click {}", button),
+            Step::Click {
+                button,
+                at: Some(p),
+            } => write!(f, "click {} {} {}", button, p.x, p.y),
+            Step::DoubleClick(button) => write!(f, "double {}", button),
+            Step::Down(button) => write!(f, "down {}", button),
+            Step::Up(button) => write!(f, "up {}", button),
+            Step::WheelUp => write!(f, "wheel up"),
+            Step::WheelDown => write!(f, "wheel down"),
+            Step::Key(chord) => write!(f, "key {}", chord),
+            Step::Type(text) => write!(f, "type {}", text),
+            Step::Wait { min, max } if min == max => write!(f, "wait {}", min.as_millis()),
+            Step::Wait { min, max } => {
+                write!(f, "wait {}-{}", min.as_millis(), max.as_millis())
+            }
+            Step::Expect { search, timeout } => {
+                let Rect { x1, y1, x2, y2 } = search.region;
+                write!(f, "expect {} {} {} {} {}", search.pic_name, x1, y1, x2, y2)?;
+                if search.sim != Similarity::default() {
+                    write!(f, " sim={}", search.sim.value())?;
+                }
+                if !search.color_p.is_empty() {
+                    write!(f, " color={}", search.color_p)?;
+                }
+                if search.dir != SearchDirection::default() {
+                    write!(f, " dir={}", i32::from(search.dir))?;
+                }
+                if search.mode != FindPicMode::default() {
+                    write!(f, " mode={}", i32::from(search.mode))?;
+                }
+                if !timeout.is_zero() {
+                    write!(f, " timeout={}", timeout.as_millis())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 按顺序执行的一组步骤
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Macro {
+    pub steps: Vec<Step>,
+}
+
+impl Macro {
+    pub fn new(steps: Vec<Step>) -> Self {
+        Self { steps }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_string())?;
+        Ok(())
+    }
+}
+
+impl FromStr for Macro {
+    type Err = Error;
+
+    /// 出错时返回的消息中带有行号
+    fn from_str(s: &str) -> Result<Self> {
+        let steps = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim();
+                !line.is_empty() && !line.starts_with('#')
+            })
+            .map(|(i, line)| {
+                parse_step(line).map_err(|msg| {
+                    Error::InvalidArgument(format!("第 {} 行 \"{}\": {}", i + 1, line.trim(), msg))
+                })
+            })
+            .collect::<Result<Vec<Step>>>()?;
+        Ok(Self { steps })
+    }
+}
+
+impl fmt::Display for Macro {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            writeln!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
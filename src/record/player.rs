@@ -0,0 +1,186 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{Macro, Step};
+use crate::pic::WaitOptions;
+use crate::{AoJia, CancellationToken, Clock, Error, Result, Rng, SystemClock};
+
+/// 检查失败的步骤，序号从 0 开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepMismatch {
+    pub iteration: u32,
+    pub step: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayReport {
+    /// 完整执行的遍数
+    pub iterations: u32,
+    /// 执行过的步骤数
+    pub steps: usize,
+    pub mismatches: Vec<StepMismatch>,
+}
+
+/// 回放宏的速度、循环次数和检查失败时的处理
+#[derive(Clone)]
+pub struct Player {
+    /// 等待时间除以该倍率，2.0 表示两倍速
+    pub speed: f64,
+    /// 循环次数，None 表示一直循环到取消
+    pub repeat: Option<u32>,
+    /// 为 true 时检查失败返回 [`Error::Mismatch`]，否则记录后继续
+    pub abort_on_mismatch: bool,
+    /// `expect` 等待图片时的轮询间隔
+    pub interval: Duration,
+    pub clock: Arc<dyn Clock>,
+    pub cancel: Option<CancellationToken>,
+    rng: Rng,
+}
+
+impl std::fmt::Debug for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Player")
+            .field("speed", &self.speed)
+            .field("repeat", &self.repeat)
+            .field("abort_on_mismatch", &self.abort_on_mismatch)
+            .field("interval", &self.interval)
+            .field("cancel", &self.cancel)
+            .finish()
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            repeat: Some(1),
+            abort_on_mismatch: true,
+            interval: Duration::from_millis(100),
+            clock: Arc::new(SystemClock),
+            cancel: None,
+            rng: Rng::default(),
+        }
+    }
+}
+
+impl Player {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn repeat(mut self, times: u32) -> Self {
+        self.repeat = Some(times);
+        self
+    }
+
+    pub fn forever(mut self) -> Self {
+        self.repeat = None;
+        self
+    }
+
+    pub fn abort_on_mismatch(mut self, abort: bool) -> Self {
+        self.abort_on_mismatch = abort;
+        self
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn cancel(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    /// 执行一个步骤，`expect` 没找到图片时返回 false
+    pub(crate) fn run_step(&mut self, aojia: &AoJia, step: &Step) -> Result<bool> {
+        match step {
+            Step::Move(p) => aojia.move_to(*p)?,
+            Step::Click { button, at } => {
+                if let Some(p) = at {
+                    aojia.move_to(*p)?;
+                }
+                aojia.click(*button)?;
+            }
+            Step::DoubleClick(button) => aojia.double_click(*button)?,
+            Step::Down(button) => aojia.mouse_down(*button)?,
+            Step::Up(button) => aojia.mouse_up(*button)?,
+            Step::WheelUp => aojia.wheel_up()?,
+            Step::WheelDown => aojia.wheel_down()?,
+            Step::Key(chord) => aojia.press_chord(chord)?,
+            Step::Type(text) => aojia.type_text(text, 0)?,
+            Step::Wait { min, max } => {
+                let secs = self.rng.uniform(min.as_secs_f64(), max.as_secs_f64());
+                let wait = Duration::from_secs_f64(secs.max(min.as_secs_f64()));
+                self.clock.sleep(wait.div_f64(self.speed));
+            }
+            Step::Expect { search, timeout } => {
+                if timeout.is_zero() {
+                    return Ok(aojia.find_pic(search)?.is_some());
+                }
+                let options = WaitOptions {
+                    timeout: *timeout,
+                    interval: self.interval,
+                    clock: self.clock.clone(),
+                    cancel: self.cancel.clone(),
+                };
+                return match aojia.wait_for_pic(search, &options) {
+                    Ok(_) => Ok(true),
+                    Err(Error::Timeout(_)) => Ok(false),
+                    Err(e) => Err(e),
+                };
+            }
+        }
+        Ok(true)
+    }
+
+    /// 回放宏，被取消时返回 [`Error::Cancelled`]
+    pub fn play(&mut self, aojia: &AoJia, script: &Macro) -> Result<PlayReport> {
+        if !(self.speed.is_finite() && self.speed > 0.0) {
+            return Err(Error::InvalidArgument(format!(
+                "回放速度 {} 必须大于 0",
+                self.speed
+            )));
+        }
+        let mut report = PlayReport::default();
+        if script.steps.is_empty() {
+            return Ok(report);
+        }
+        while self.repeat.is_none_or(|n| report.iterations < n) {
+            for (i, step) in script.steps.iter().enumerate() {
+                if self.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+                    return Err(Error::Cancelled);
+                }
+                let matched = self.run_step(aojia, step)?;
+                report.steps += 1;
+                if !matched {
+                    if self.abort_on_mismatch {
+                        return Err(Error::Mismatch(format!(
+                            "第 {} 遍第 {} 步 {}",
+                            report.iterations + 1,
+                            i + 1,
+                            step
+                        )));
+                    }
+                    report.mismatches.push(StepMismatch {
+                        iteration: report.iterations,
+                        step: i,
+                    });
+                }
+            }
+            report.iterations += 1;
+        }
+        Ok(report)
+    }
+}
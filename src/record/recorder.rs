@@ -0,0 +1,72 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::{Macro, Player, Step};
+use crate::{AoJia, Clock, Error, Result, SystemClock};
+
+/// 执行步骤的同时把它们记录下来，步骤之间经过的时间记录为 `wait`
+#[derive(Debug)]
+pub struct Recorder<'a> {
+    aojia: &'a AoJia,
+    steps: Vec<Step>,
+    player: Player,
+    last: Instant,
+    /// 短于该时间的间隔不记录
+    min_wait: Duration,
+}
+
+impl<'a> Recorder<'a> {
+    pub fn new(aojia: &'a AoJia) -> Self {
+        Self::with_clock(aojia, SystemClock)
+    }
+
+    pub fn with_clock(aojia: &'a AoJia, clock: impl Clock + 'static) -> Self {
+        let mut player = Player::default();
+        player.clock = Arc::new(clock);
+        Self {
+            aojia,
+            steps: Vec::new(),
+            last: player.clock.now(),
+            player,
+            min_wait: Duration::from_millis(50),
+        }
+    }
+
+    pub fn min_wait(mut self, min_wait: Duration) -> Self {
+        self.min_wait = min_wait;
+        self
+    }
+
+    /// 执行并记录一个步骤，`expect` 没找到图片时返回 [`Error::Mismatch`] 且不记录。
+    /// 宏文件中无法保存的步骤（带换行的 `type`）在执行前返回 [`Error::InvalidArgument`]
+    pub fn step(&mut self, step: Step) -> Result<()> {
+        if let Step::Type(text) = &step
+            && text.contains(['\n', '\r'])
+        {
+            return Err(Error::InvalidArgument(
+                "type 的文字中不能有换行".to_string(),
+            ));
+        }
+        let gap = self.player.clock.now().duration_since(self.last);
+        let matched = self.player.run_step(self.aojia, &step)?;
+        if !matched {
+            return Err(Error::Mismatch(step.to_string()));
+        }
+        if gap >= self.min_wait && !matches!(step, Step::Wait { .. }) {
+            let gap = Duration::from_millis(gap.as_millis() as u64);
+            self.steps.push(Step::Wait { min: gap, max: gap });
+        }
+        self.steps.push(step);
+        self.last = self.player.clock.now();
+        Ok(())
+    }
+
+    /// 解析一行宏文本后执行并记录
+    pub fn line(&mut self, line: &str) -> Result<()> {
+        self.step(line.parse()?)
+    }
+
+    pub fn finish(self) -> Macro {
+        Macro::new(self.steps)
+    }
+}
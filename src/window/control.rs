@@ -0,0 +1,129 @@
+use crate::error::check;
+use crate::{AoJia, Error, Hwnd, Point, Result};
+
+/// SetWindowState 的 Flag 参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowState {
+    Close = 0,
+    /// 激活并置于前台
+    Activate = 1,
+    Minimize = 2,
+    /// 最小化并释放内存
+    MinimizeAll = 3,
+    Maximize = 4,
+    /// 从最小化或最大化恢复
+    Restore = 5,
+    Hide = 6,
+    Show = 7,
+    TopMost = 8,
+    /// 取消置顶
+    NoTopMost = 9,
+}
+
+impl From<WindowState> for i32 {
+    fn from(state: WindowState) -> Self {
+        state as i32
+    }
+}
+
+impl TryFrom<i32> for WindowState {
+    type Error = Error;
+
+    fn try_from(flag: i32) -> Result<Self> {
+        match flag {
+            0 => Ok(Self::Close),
+            1 => Ok(Self::Activate),
+            2 => Ok(Self::Minimize),
+            3 => Ok(Self::MinimizeAll),
+            4 => Ok(Self::Maximize),
+            5 => Ok(Self::Restore),
+            6 => Ok(Self::Hide),
+            7 => Ok(Self::Show),
+            8 => Ok(Self::TopMost),
+            9 => Ok(Self::NoTopMost),
+            _ => Err(Error::InvalidArgument(format!(
+                "Flag {} 不在 0 ~ 9 之间",
+                flag
+            ))),
+        }
+    }
+}
+
+/// GetWindow 的 Flag 参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowRelation {
+    Parent = 0,
+    /// 第一个子窗口
+    FirstChild = 1,
+    /// 同级的第一个窗口
+    First = 2,
+    /// 同级的最后一个窗口
+    Last = 3,
+    Next = 4,
+    Previous = 5,
+    Owner = 6,
+    /// 顶层窗口
+    Top = 7,
+}
+
+impl From<WindowRelation> for i32 {
+    fn from(relation: WindowRelation) -> Self {
+        relation as i32
+    }
+}
+
+impl AoJia {
+    /// 设置窗口大小，包括标题栏和边框
+    pub fn set_window_size(&self, hwnd: Hwnd, width: i32, height: i32) -> Result<()> {
+        if width <= 0 || height <= 0 {
+            return Err(Error::InvalidArgument(format!(
+                "窗口大小 {}x{} 不合法",
+                width, height
+            )));
+        }
+        check("SetWindowSize", self.SetWindowSize(hwnd, width, height)?)
+    }
+
+    /// 把窗口左上角移动到屏幕坐标 `pos`
+    pub fn move_window(&self, hwnd: Hwnd, pos: Point) -> Result<()> {
+        check("MoveWindow", self.MoveWindow(hwnd, pos.x, pos.y)?)
+    }
+
+    pub fn set_window_state(&self, hwnd: Hwnd, state: WindowState) -> Result<()> {
+        check("SetWindowState", self.SetWindowState(hwnd, state.into())?)
+    }
+
+    pub fn set_window_title(&self, hwnd: Hwnd, title: &str) -> Result<()> {
+        check("SetWindowTitle", self.SetWindowTitle(hwnd, title)?)
+    }
+
+    pub fn window_title(&self, hwnd: Hwnd) -> Result<String> {
+        self.GetWindowTitle(hwnd)
+    }
+
+    pub fn window_class(&self, hwnd: Hwnd) -> Result<String> {
+        self.GetWindowClass(hwnd)
+    }
+
+    /// 窗口所属的进程 ID
+    pub fn window_pid(&self, hwnd: Hwnd) -> Result<i32> {
+        let pid = self.GetWindowProcessId(hwnd)?;
+        check("GetWindowProcessId", pid)?;
+        Ok(pid)
+    }
+
+    /// 按关系查找相关窗口，没有时返回 None
+    pub fn related_window(&self, hwnd: Hwnd, relation: WindowRelation) -> Result<Option<Hwnd>> {
+        let ret = self.GetWindow(hwnd, relation.into())?;
+        Ok((ret > 0).then_some(ret))
+    }
+
+    pub fn parent_window(&self, hwnd: Hwnd) -> Result<Option<Hwnd>> {
+        self.related_window(hwnd, WindowRelation::Parent)
+    }
+
+    /// 第一个子窗口
+    pub fn child_window(&self, hwnd: Hwnd) -> Result<Option<Hwnd>> {
+        self.related_window(hwnd, WindowRelation::FirstChild)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{AoJia, Error, Hwnd, Result};
+
+use super::WindowQuery;
+
+/// 窗口的基本信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    pub hwnd: Hwnd,
+    pub title: String,
+    pub class: String,
+    pub pid: i32,
+    /// 客户区宽度
+    pub width: i32,
+    /// 客户区高度
+    pub height: i32,
+}
+
+/// 解析 EnumWindow 的返回值，句柄用 `|` 或 `,` 分隔
+pub fn parse_hwnd_list(result: &str) -> Result<Vec<Hwnd>> {
+    result
+        .split(['|', ','])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            item.parse()
+                .map_err(|_| Error::Convert(format!("无法解析窗口句柄 \"{}\"", item)))
+        })
+        .collect()
+}
+
+impl AoJia {
+    /// 按条件枚举所有匹配的窗口句柄
+    pub fn enum_windows(&self, query: &WindowQuery) -> Result<Vec<Hwnd>> {
+        let args = query.encode()?;
+        let ret = self.EnumWindow(
+            args.parent,
+            &args.pro_name,
+            args.pro_id,
+            &args.class,
+            &args.title,
+            args.ty,
+            args.t,
+        )?;
+        parse_hwnd_list(&ret)
+    }
+
+    /// 读取窗口的标题、类名、进程 ID 和客户区大小，窗口已关闭时返回 [`Error::WindowGone`]
+    pub fn window_info(&self, hwnd: Hwnd) -> Result<WindowInfo> {
+        let (mut width, mut height) = (0, 0);
+        if self.GetClientSize(hwnd, &mut width, &mut height)? == 0 {
+            return Err(Error::WindowGone(hwnd));
+        }
+        Ok(WindowInfo {
+            hwnd,
+            title: self.GetWindowTitle(hwnd)?,
+            class: self.GetWindowClass(hwnd)?,
+            pid: self.GetWindowProcessId(hwnd)?,
+            width,
+            height,
+        })
+    }
+
+    /// 枚举窗口并读取每个窗口的信息，枚举后已关闭的窗口会被跳过
+    pub fn enum_window_infos(&self, query: &WindowQuery) -> Result<Vec<WindowInfo>> {
+        let mut infos = Vec::new();
+        for hwnd in self.enum_windows(query)? {
+            match self.window_info(hwnd) {
+                Ok(info) => infos.push(info),
+                Err(Error::WindowGone(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(infos)
+    }
+}
@@ -0,0 +1,9 @@
+mod control;
+mod enumerate;
+mod query;
+mod watch;
+
+pub use control::{WindowRelation, WindowState};
+pub use enumerate::{WindowInfo, parse_hwnd_list};
+pub use query::{FindWindowArgs, TitleMatch, WindowQuery};
+pub use watch::{WatchHandle, WatchOptions, WindowEvent, WindowSnapshot, WindowWatcher};
@@ -0,0 +1,152 @@
+//! FindWindow 的查询条件。
+//!
+//! `Type` 为按位组合的查找条件：
+//! - 1: 匹配进程名，2: 匹配进程 ID，4: 匹配类名
+//! - 8: 标题完全相同，16: 标题包含
+//! - 32: 只找可见窗口
+//!
+//! `T` 为找不到时继续查找的毫秒数，0 表示只找一次。
+
+use std::time::Duration;
+
+use crate::{AoJia, Error, Hwnd, Result};
+
+const BY_PROCESS_NAME: i32 = 1;
+const BY_PID: i32 = 2;
+const BY_CLASS: i32 = 4;
+const BY_TITLE: i32 = 8;
+const BY_TITLE_CONTAINS: i32 = 16;
+const VISIBLE_ONLY: i32 = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TitleMatch {
+    Exact(String),
+    Contains(String),
+}
+
+/// 按顺序对应 FindWindow 的 7 个参数
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FindWindowArgs {
+    pub parent: Hwnd,
+    pub pro_name: String,
+    pub pro_id: i32,
+    pub class: String,
+    pub title: String,
+    pub ty: i32,
+    pub t: i32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct WindowQuery {
+    parent: Hwnd,
+    process_name: Option<String>,
+    pid: Option<i32>,
+    class: Option<String>,
+    title: Option<TitleMatch>,
+    visible_only: bool,
+    timeout: Duration,
+}
+
+impl WindowQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只在该窗口的子窗口中查找，默认为 0 即查找顶层窗口
+    pub fn parent(mut self, hwnd: Hwnd) -> Self {
+        self.parent = hwnd;
+        self
+    }
+
+    pub fn process_name(mut self, name: &str) -> Self {
+        self.process_name = Some(name.to_string());
+        self
+    }
+
+    pub fn pid(mut self, pid: i32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    pub fn class(mut self, class: &str) -> Self {
+        self.class = Some(class.to_string());
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(TitleMatch::Exact(title.to_string()));
+        self
+    }
+
+    pub fn title_contains(mut self, title: &str) -> Self {
+        self.title = Some(TitleMatch::Contains(title.to_string()));
+        self
+    }
+
+    pub fn visible_only(mut self) -> Self {
+        self.visible_only = true;
+        self
+    }
+
+    /// 找不到时继续查找的时间，精度为毫秒
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 转换为 FindWindow 的参数，没有任何条件时返回错误
+    pub fn encode(&self) -> Result<FindWindowArgs> {
+        let mut args = FindWindowArgs {
+            parent: self.parent,
+            t: self.timeout.as_millis().min(i32::MAX as u128) as i32,
+            ..Default::default()
+        };
+        if let Some(name) = &self.process_name {
+            args.pro_name = name.clone();
+            args.ty |= BY_PROCESS_NAME;
+        }
+        if let Some(pid) = self.pid {
+            args.pro_id = pid;
+            args.ty |= BY_PID;
+        }
+        if let Some(class) = &self.class {
+            args.class = class.clone();
+            args.ty |= BY_CLASS;
+        }
+        match &self.title {
+            Some(TitleMatch::Exact(title)) => {
+                args.title = title.clone();
+                args.ty |= BY_TITLE;
+            }
+            Some(TitleMatch::Contains(title)) => {
+                args.title = title.clone();
+                args.ty |= BY_TITLE_CONTAINS;
+            }
+            None => {}
+        }
+        if args.ty == 0 {
+            return Err(Error::InvalidArgument("没有指定查找窗口的条件".to_string()));
+        }
+        if self.visible_only {
+            args.ty |= VISIBLE_ONLY;
+        }
+        Ok(args)
+    }
+}
+
+impl AoJia {
+    /// 按条件查找窗口，找不到时返回 None
+    pub fn find_window(&self, query: &WindowQuery) -> Result<Option<Hwnd>> {
+        let args = query.encode()?;
+        let hwnd = self.FindWindow(
+            args.parent,
+            &args.pro_name,
+            args.pro_id,
+            &args.class,
+            &args.title,
+            args.ty,
+            args.t,
+        )?;
+        Ok((hwnd > 0).then_some(hwnd))
+    }
+}
@@ -0,0 +1,292 @@
+//! 窗口生命周期监视。
+//!
+//! 每次轮询用 EnumWindow 找出匹配的窗口，再读取客户区大小、客户区原点和标题，
+//! 与上次报告的状态比较后产生事件。同一变化要连续 `settle` 次轮询结果相同才会报告，
+//! 拖动窗口时的中间位置和一闪而过的窗口不会产生重复事件。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use super::WindowQuery;
+use crate::{AoJia, CancellationToken, Clock, Error, Hwnd, Point, Result, SystemClock};
+
+/// 一次轮询时窗口的状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowSnapshot {
+    pub hwnd: Hwnd,
+    pub title: String,
+    /// 客户区宽度
+    pub width: i32,
+    /// 客户区高度
+    pub height: i32,
+    /// 客户区左上角的屏幕坐标
+    pub origin: Point,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowEvent {
+    Appeared(WindowSnapshot),
+    Closed(Hwnd),
+    Resized { hwnd: Hwnd, width: i32, height: i32 },
+    Moved { hwnd: Hwnd, origin: Point },
+    TitleChanged { hwnd: Hwnd, title: String },
+}
+
+/// 轮询间隔、防抖次数、时钟和取消
+#[derive(Clone)]
+pub struct WatchOptions {
+    pub interval: Duration,
+    /// 变化连续出现多少次轮询才报告，至少为 1
+    pub settle: u32,
+    pub clock: Arc<dyn Clock>,
+    pub cancel: Option<CancellationToken>,
+}
+
+impl std::fmt::Debug for WatchOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchOptions")
+            .field("interval", &self.interval)
+            .field("settle", &self.settle)
+            .field("cancel", &self.cancel)
+            .finish()
+    }
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(500),
+            settle: 1,
+            clock: Arc::new(SystemClock),
+            cancel: None,
+        }
+    }
+}
+
+impl WatchOptions {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            ..Default::default()
+        }
+    }
+
+    pub fn settle(mut self, polls: u32) -> Self {
+        self.settle = polls.max(1);
+        self
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn cancel(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Tracked {
+    reported: Option<WindowSnapshot>,
+    /// 与已报告状态不同的观察结果及其连续出现的次数
+    pending: Option<(Option<WindowSnapshot>, u32)>,
+}
+
+/// 比较两次报告之间的状态，产生事件
+fn diff(
+    hwnd: Hwnd,
+    old: Option<&WindowSnapshot>,
+    new: Option<&WindowSnapshot>,
+) -> Vec<WindowEvent> {
+    match (old, new) {
+        (None, Some(new)) => vec![WindowEvent::Appeared(new.clone())],
+        (Some(_), None) => vec![WindowEvent::Closed(hwnd)],
+        (Some(old), Some(new)) => {
+            let mut events = Vec::new();
+            if (old.width, old.height) != (new.width, new.height) {
+                events.push(WindowEvent::Resized {
+                    hwnd,
+                    width: new.width,
+                    height: new.height,
+                });
+            }
+            if old.origin != new.origin {
+                events.push(WindowEvent::Moved {
+                    hwnd,
+                    origin: new.origin,
+                });
+            }
+            if old.title != new.title {
+                events.push(WindowEvent::TitleChanged {
+                    hwnd,
+                    title: new.title.clone(),
+                });
+            }
+            events
+        }
+        (None, None) => vec![],
+    }
+}
+
+/// 按查询条件监视窗口，可以手动调用 [`poll`](Self::poll)，也可以放到后台线程运行
+#[derive(Debug)]
+pub struct WindowWatcher {
+    query: WindowQuery,
+    options: WatchOptions,
+    windows: HashMap<Hwnd, Tracked>,
+}
+
+impl WindowWatcher {
+    /// 查询条件中的超时会被忽略，每次轮询只查找一次
+    pub fn new(query: WindowQuery, options: WatchOptions) -> Self {
+        Self {
+            query: query.timeout(Duration::ZERO),
+            options,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// 读取窗口状态，窗口已关闭时返回 None
+    fn snapshot(aojia: &AoJia, hwnd: Hwnd) -> Result<Option<WindowSnapshot>> {
+        let (mut width, mut height) = (0, 0);
+        if aojia.GetClientSize(hwnd, &mut width, &mut height)? == 0 {
+            return Ok(None);
+        }
+        let origin = match aojia.client_origin(hwnd) {
+            Ok(origin) => origin,
+            // 两次调用之间窗口被关闭
+            Err(Error::Failed {
+                fun: "ClientToScreen",
+                ..
+            }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Ok(Some(WindowSnapshot {
+            hwnd,
+            title: aojia.GetWindowTitle(hwnd)?,
+            width,
+            height,
+            origin,
+        }))
+    }
+
+    /// 轮询一次，返回与上次报告相比的变化
+    pub fn poll(&mut self, aojia: &AoJia) -> Result<Vec<WindowEvent>> {
+        let mut observed = HashMap::new();
+        for hwnd in aojia.enum_windows(&self.query)? {
+            if let Some(snapshot) = Self::snapshot(aojia, hwnd)? {
+                observed.insert(hwnd, snapshot);
+            }
+        }
+        for hwnd in observed.keys() {
+            self.windows.entry(*hwnd).or_default();
+        }
+
+        let mut hwnds: Vec<Hwnd> = self.windows.keys().copied().collect();
+        hwnds.sort_unstable();
+        let mut events = Vec::new();
+        for hwnd in hwnds {
+            let current = observed.remove(&hwnd);
+            let tracked = self.windows.get_mut(&hwnd).expect("已加入");
+            if current == tracked.reported {
+                tracked.pending = None;
+            } else {
+                let count = match &tracked.pending {
+                    Some((pending, count)) if *pending == current => count + 1,
+                    _ => 1,
+                };
+                if count >= self.options.settle {
+                    events.extend(diff(hwnd, tracked.reported.as_ref(), current.as_ref()));
+                    tracked.reported = current;
+                    tracked.pending = None;
+                } else {
+                    tracked.pending = Some((current, count));
+                }
+            }
+            if tracked.reported.is_none() && tracked.pending.is_none() {
+                self.windows.remove(&hwnd);
+            }
+        }
+        Ok(events)
+    }
+
+    /// 在后台线程中轮询，事件通过通道发出。
+    ///
+    /// COM 对象不能跨线程使用，所以由 `connect` 在后台线程中创建 [`AoJia`]。
+    /// 轮询出错时发出错误并结束线程。
+    pub fn spawn<F>(mut self, connect: F) -> WatchHandle
+    where
+        F: FnOnce() -> Result<AoJia> + Send + 'static,
+    {
+        let cancel = self.options.cancel.clone().unwrap_or_default();
+        let token = cancel.clone();
+        let (tx, events) = mpsc::channel();
+        let thread = std::thread::spawn(move || {
+            let aojia = match connect() {
+                Ok(aojia) => aojia,
+                Err(e) => {
+                    let _ = tx.send(Err(e));
+                    return;
+                }
+            };
+            while !token.is_cancelled() {
+                match self.poll(&aojia) {
+                    Ok(polled) => {
+                        for event in polled {
+                            if tx.send(Ok(event)).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        return;
+                    }
+                }
+                self.options.clock.sleep(self.options.interval);
+            }
+        });
+        WatchHandle {
+            cancel,
+            thread: Some(thread),
+            events,
+        }
+    }
+}
+
+/// 后台监视线程的句柄，drop 时停止线程
+#[derive(Debug)]
+pub struct WatchHandle {
+    cancel: CancellationToken,
+    thread: Option<JoinHandle<()>>,
+    events: Receiver<Result<WindowEvent>>,
+}
+
+impl WatchHandle {
+    pub fn events(&self) -> &Receiver<Result<WindowEvent>> {
+        &self.events
+    }
+
+    /// 停止后台线程并等待其结束
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.cancel.cancel();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
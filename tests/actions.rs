@@ -0,0 +1,339 @@
+mod common;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::binding::{KeyboardMode, MouseMode, ScreenMode};
+use aojia::input::{ActionOptions, MouseButton, Trajectory, Verify};
+use aojia::pic::{Bitmap, PicSearch, Similarity, WaitOptions};
+use aojia::{AoJia, BindMode, Error, MockBackend, MockClock, Point, Rng, Value};
+use common::{in_i32, set_out};
+
+fn mock() -> MockBackend {
+    MockBackend::succeeding(&["MoveTo", "LeftClick", "RightClick", "LeftDown", "LeftUp"])
+}
+
+/// 第 `i` 次调用 MoveTo 的坐标
+fn moves(mock: &MockBackend) -> Vec<Point> {
+    mock.calls()
+        .into_iter()
+        .filter(|c| c.name == "MoveTo")
+        .map(|c| match &c.args[..] {
+            [RecordedArg::In(x), RecordedArg::In(y)] => {
+                Point::new(x.to_i32().unwrap(), y.to_i32().unwrap())
+            }
+            args => panic!("{:?}", args),
+        })
+        .collect()
+}
+
+#[test]
+fn click_at_with_jitter() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let options = ActionOptions::default().jitter(3);
+    let mut rng = Rng::new(4);
+    for _ in 0..20 {
+        let clicked = aojia
+            .click_at(Point::new(100, 200), MouseButton::Right, &options, &mut rng)
+            .unwrap();
+        assert!((97..=103).contains(&clicked.x) && (197..=203).contains(&clicked.y));
+        assert_eq!(moves(&mock).last(), Some(&clicked));
+    }
+    assert_eq!(mock.call_names()[..2], ["MoveTo", "RightClick"]);
+}
+
+#[test]
+fn drag_sequence() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia
+        .drag(
+            Point::new(10, 10),
+            Point::new(50, 60),
+            &ActionOptions::default(),
+            &mut Rng::new(0),
+        )
+        .unwrap();
+    assert_eq!(
+        mock.call_names(),
+        ["MoveTo", "LeftDown", "MoveTo", "LeftUp"]
+    );
+    assert_eq!(moves(&mock), [Point::new(10, 10), Point::new(50, 60)]);
+}
+
+#[test]
+fn drag_along_trajectory() {
+    let mock = mock();
+    mock.on("GetCursorPos", |args| {
+        set_out(args, 0, 0);
+        set_out(args, 1, 0);
+        Ok(Value::I32(1))
+    });
+    let aojia = AoJia::with_backend(mock.clone());
+    let options =
+        ActionOptions::default().trajectory(Trajectory::default().clock(MockClock::new()));
+    aojia
+        .drag(
+            Point::new(100, 100),
+            Point::new(400, 300),
+            &options,
+            &mut Rng::new(1),
+        )
+        .unwrap();
+    let names = mock.call_names();
+    let down = names.iter().position(|n| n == "LeftDown").unwrap();
+    assert_eq!(names[0], "GetCursorPos");
+    assert!(down > 2);
+    assert!(
+        names[down + 1..names.len() - 1]
+            .iter()
+            .all(|n| n == "MoveTo")
+    );
+    assert!(names.len() - down > 3);
+    assert_eq!(names.last().unwrap(), "LeftUp");
+    assert_eq!(moves(&mock).last(), Some(&Point::new(400, 300)));
+}
+
+/// GetCursorPos 返回屏幕坐标 `pos`
+fn cursor_at(mock: &MockBackend, pos: Point) {
+    mock.on("GetCursorPos", move |args| {
+        set_out(args, 0, pos.x);
+        set_out(args, 1, pos.y);
+        Ok(Value::I32(1))
+    });
+}
+
+#[test]
+fn trajectory_starts_from_client_cursor_when_bound() {
+    let options =
+        ActionOptions::default().trajectory(Trajectory::default().clock(MockClock::new()));
+    let target = Point::new(400, 300);
+
+    let unbound = mock();
+    cursor_at(&unbound, Point::new(200, 150));
+    AoJia::with_backend(unbound.clone())
+        .click_at(target, MouseButton::Left, &options, &mut Rng::new(3))
+        .unwrap();
+
+    // 客户区原点在屏幕 (100, 50)，屏幕上的 (300, 200) 即客户区的 (200, 150)
+    let bound = mock();
+    bound.succeed(&["KQHouTai", "GBHouTai"]);
+    cursor_at(&bound, Point::new(300, 200));
+    bound.on("ClientOrScreen", |args| {
+        assert_eq!(in_i32(args, 5), 1);
+        let (x, y) = (in_i32(args, 1) - 100, in_i32(args, 2) - 50);
+        set_out(args, 3, x);
+        set_out(args, 4, y);
+        Ok(Value::I32(1))
+    });
+    let aojia = AoJia::with_backend(bound.clone());
+    let mode = BindMode::new(ScreenMode::Gdi, KeyboardMode::Windows, MouseMode::Windows);
+    let _guard = aojia.bind(7, mode).unwrap();
+    aojia
+        .click_at(target, MouseButton::Left, &options, &mut Rng::new(3))
+        .unwrap();
+
+    assert!(moves(&unbound).len() > 2);
+    assert_eq!(moves(&bound), moves(&unbound));
+}
+
+#[test]
+fn drag_releases_on_failure() {
+    let mock = mock();
+    let mut count = 0;
+    mock.on("MoveTo", move |_| {
+        count += 1;
+        Ok(Value::I32((count == 1) as i32))
+    });
+    let aojia = AoJia::with_backend(mock.clone());
+    let ret = aojia.drag(
+        Point::new(10, 10),
+        Point::new(50, 60),
+        &ActionOptions::default(),
+        &mut Rng::new(0),
+    );
+    assert!(matches!(ret, Err(Error::Failed { fun: "MoveTo", .. })));
+    assert_eq!(
+        mock.call_names(),
+        ["MoveTo", "LeftDown", "MoveTo", "LeftUp"]
+    );
+}
+
+/// 写出 24 位 BMP
+fn write_bmp(path: &PathBuf, pic: &Bitmap) {
+    let (w, h) = (pic.width(), pic.height());
+    let row = (w * 3).div_ceil(4) * 4;
+    let size = 54 + row * h;
+    let mut data = Vec::new();
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&size.to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&54u32.to_le_bytes());
+    data.extend_from_slice(&40u32.to_le_bytes());
+    data.extend_from_slice(&(w as i32).to_le_bytes());
+    data.extend_from_slice(&(h as i32).to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&24u16.to_le_bytes());
+    data.extend_from_slice(&[0; 24]);
+    for y in (0..h).rev() {
+        for x in 0..w {
+            let c = pic.pixel(x, y);
+            data.extend_from_slice(&[c as u8, (c >> 8) as u8, (c >> 16) as u8]);
+        }
+        data.resize(data.len() + (row - w * 3) as usize, 0);
+    }
+    std::fs::write(path, data).unwrap();
+}
+
+fn pic_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aojia-actions-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn button() -> Bitmap {
+    let mut pic = Bitmap::new(20, 12, 0x000000);
+    for x in 0..20 {
+        pic.set_pixel(x, 0, 0xFFFFFF);
+        pic.set_pixel(x, 11, 0x00FF00);
+    }
+    pic
+}
+
+#[test]
+fn click_pic_inside_template_and_verify() {
+    let dir = pic_dir("click");
+    write_bmp(&dir.join("ok.bmp"), &button());
+    let mut screen = Bitmap::new(200, 100, 0x336699);
+    screen.paste(60, 30, &button());
+    let mock = mock();
+    mock.set_screen(screen);
+    let m = mock.clone();
+    mock.on("LeftClick", move |_| {
+        m.set_screen(Bitmap::new(200, 100, 0x336699));
+        Ok(Value::I32(1))
+    });
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.SetPath(dir.to_str().unwrap()).unwrap();
+    assert_eq!(aojia.pic_size("ok.bmp").unwrap(), (20, 12));
+
+    let search = PicSearch::new((0, 0, 199, 99), "ok.bmp").sim(Similarity::EXACT);
+    let options = ActionOptions::default().verify(
+        Verify::PicGone(search.clone()),
+        WaitOptions::new(Duration::from_secs(1), Duration::from_millis(100))
+            .clock(MockClock::new()),
+    );
+    let found = aojia
+        .click_pic(&search, MouseButton::Left, &options, &mut Rng::new(2))
+        .unwrap()
+        .unwrap();
+    assert_eq!((found.x, found.y), (60, 30));
+    let clicked = moves(&mock)[0];
+    assert!((65..=75).contains(&clicked.x), "{:?}", clicked);
+    assert!((33..=39).contains(&clicked.y), "{:?}", clicked);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn click_pic_not_found() {
+    let mock = mock();
+    mock.set_screen(Bitmap::new(50, 50, 0));
+    mock.add_pic("ok.bmp", button());
+    let aojia = AoJia::with_backend(mock.clone());
+    let search = PicSearch::new((0, 0, 49, 49), "ok.bmp");
+    let ret = aojia
+        .click_pic(
+            &search,
+            MouseButton::Left,
+            &ActionOptions::default(),
+            &mut Rng::new(0),
+        )
+        .unwrap();
+    assert_eq!(ret, None);
+    assert!(!mock.call_names().contains(&"LeftClick".to_string()));
+}
+
+#[test]
+fn verification_timeout() {
+    let mock = mock();
+    mock.set_screen(Bitmap::new(50, 50, 0));
+    mock.add_pic("next.bmp", button());
+    let aojia = AoJia::with_backend(mock);
+    let options = ActionOptions::default().verify(
+        Verify::PicAppears(PicSearch::new((0, 0, 49, 49), "next.bmp")),
+        WaitOptions::new(Duration::from_secs(1), Duration::from_millis(100))
+            .clock(MockClock::new()),
+    );
+    assert!(matches!(
+        aojia.click_at(
+            Point::new(5, 5),
+            MouseButton::Left,
+            &options,
+            &mut Rng::new(0)
+        ),
+        Err(Error::Timeout(_))
+    ));
+}
+
+#[test]
+fn click_pic_does_not_add_jitter() {
+    let dir = pic_dir("jitter");
+    write_bmp(&dir.join("ok.bmp"), &button());
+    let mut screen = Bitmap::new(200, 100, 0x336699);
+    screen.paste(60, 30, &button());
+    let mock = mock();
+    mock.set_screen(screen);
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.SetPath(dir.to_str().unwrap()).unwrap();
+
+    let search = PicSearch::new((0, 0, 199, 99), "ok.bmp").sim(Similarity::EXACT);
+    let options = ActionOptions::default().jitter(50);
+    let mut rng = Rng::new(9);
+    for _ in 0..20 {
+        aojia
+            .click_pic(&search, MouseButton::Left, &options, &mut rng)
+            .unwrap()
+            .unwrap();
+    }
+    for clicked in moves(&mock) {
+        assert!((65..=75).contains(&clicked.x), "{:?}", clicked);
+        assert!((33..=39).contains(&clicked.y), "{:?}", clicked);
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn click_pic_without_size_is_error() {
+    let dir = pic_dir("nosize");
+    std::fs::write(dir.join("bad.bmp"), b"BM not a bitmap").unwrap();
+    let mock = mock();
+    let mut screen = Bitmap::new(50, 50, 0);
+    screen.paste(10, 10, &button());
+    mock.set_screen(screen);
+    // 只在内存中，SetPath 目录里没有这张图或文件已损坏
+    mock.add_pic("ok.bmp", button());
+    mock.add_pic("bad.bmp", button());
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.SetPath(dir.to_str().unwrap()).unwrap();
+    assert!(matches!(aojia.pic_size("ok.bmp"), Err(Error::Io(_))));
+    assert!(matches!(aojia.pic_size("bad.bmp"), Err(Error::Image(_))));
+
+    for (name, image) in [("ok.bmp", false), ("bad.bmp", true)] {
+        let search = PicSearch::new((0, 0, 49, 49), name).sim(Similarity::EXACT);
+        let ret = aojia.click_pic(
+            &search,
+            MouseButton::Left,
+            &ActionOptions::default(),
+            &mut Rng::new(0),
+        );
+        match ret {
+            Err(Error::Image(_)) => assert!(image, "{}", name),
+            Err(Error::Io(_)) => assert!(!image, "{}", name),
+            other => panic!("{}: {:?}", name, other),
+        }
+    }
+    assert!(!mock.call_names().contains(&"LeftClick".to_string()));
+    assert!(moves(&mock).is_empty());
+}
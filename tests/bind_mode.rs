@@ -0,0 +1,141 @@
+use aojia::backend::mock::RecordedArg;
+use aojia::binding::{BindFlags, KeyboardMode, MouseMode, ScreenMode};
+use aojia::{AoJia, BindMode, Error, MockBackend, Value};
+
+#[test]
+fn screen_round_trip() {
+    for mode in ScreenMode::ALL {
+        assert_eq!(mode.to_string().parse::<ScreenMode>().unwrap(), *mode);
+    }
+    let names: Vec<&str> = ScreenMode::ALL.iter().map(|m| m.as_str()).collect();
+    assert_eq!(names, ["normal", "gdi", "gdi2", "dx", "dx2"]);
+}
+
+#[test]
+fn keyboard_round_trip() {
+    for mode in KeyboardMode::ALL {
+        assert_eq!(mode.to_string().parse::<KeyboardMode>().unwrap(), *mode);
+    }
+    let names: Vec<&str> = KeyboardMode::ALL.iter().map(|m| m.as_str()).collect();
+    assert_eq!(names, ["normal", "windows", "dx"]);
+}
+
+#[test]
+fn mouse_round_trip() {
+    for mode in MouseMode::ALL {
+        assert_eq!(mode.to_string().parse::<MouseMode>().unwrap(), *mode);
+    }
+    let names: Vec<&str> = MouseMode::ALL.iter().map(|m| m.as_str()).collect();
+    assert_eq!(names, ["normal", "windows", "windows2", "dx"]);
+}
+
+#[test]
+fn unknown_modes() {
+    assert!("GDI".parse::<ScreenMode>().is_err());
+    assert!("".parse::<KeyboardMode>().is_err());
+    assert!("dx3".parse::<MouseMode>().is_err());
+    assert!("dx.public.unknown".parse::<BindFlags>().is_err());
+}
+
+/// 所有 Flag 组合都能序列化后解析回来
+#[test]
+fn flags_round_trip_all_combinations() {
+    let count = BindFlags::ALL.len();
+    for bits in 0u32..(1 << count) {
+        let mut flags = BindFlags::NONE;
+        for (i, (flag, _)) in BindFlags::ALL.iter().enumerate() {
+            if bits & (1 << i) != 0 {
+                flags |= *flag;
+            }
+        }
+        let s = flags.to_string();
+        assert_eq!(s.parse::<BindFlags>().unwrap(), flags, "{}", s);
+        assert_eq!(
+            s.split('|').filter(|p| !p.is_empty()).count(),
+            bits.count_ones() as usize
+        );
+    }
+}
+
+#[test]
+fn flags_strings() {
+    assert_eq!(BindFlags::NONE.to_string(), "");
+    assert_eq!(
+        (BindFlags::LOCK_KEYBOARD | BindFlags::ACTIVE_API).to_string(),
+        "dx.public.active.api|dx.keypad.input.lock.api"
+    );
+    assert_eq!(
+        " dx.public.hide.dll | dx.public.input.ime "
+            .parse::<BindFlags>()
+            .unwrap(),
+        BindFlags::HIDE_DLL | BindFlags::INPUT_IME
+    );
+}
+
+#[test]
+fn parse_mode() {
+    let mode = BindMode::parse("dx2", "dx", "dx", "dx.mouse.position.lock.api", 1).unwrap();
+    assert_eq!(
+        mode,
+        BindMode::new(ScreenMode::Dx2, KeyboardMode::Dx, MouseMode::Dx)
+            .flags(BindFlags::LOCK_MOUSE_POSITION)
+            .ty(1)
+    );
+}
+
+#[test]
+fn incompatible_combinations() {
+    let dx = BindMode::new(ScreenMode::Dx, KeyboardMode::Dx, MouseMode::Dx);
+    assert!(dx.clone().flags(BindFlags::ACTIVE_API).validate().is_ok());
+    let cases = [
+        dx.clone()
+            .flags(BindFlags::ACTIVE_API | BindFlags::ACTIVE_MESSAGE),
+        BindMode::new(ScreenMode::Gdi, KeyboardMode::Windows, MouseMode::Windows)
+            .flags(BindFlags::HIDE_DLL),
+        BindMode::new(ScreenMode::Dx, KeyboardMode::Dx, MouseMode::Windows)
+            .flags(BindFlags::LOCK_MOUSE_CLIP),
+        BindMode::new(ScreenMode::Dx, KeyboardMode::Windows, MouseMode::Dx)
+            .flags(BindFlags::LOCK_KEYBOARD),
+    ];
+    for mode in cases {
+        assert!(
+            matches!(mode.validate(), Err(Error::InvalidArgument(_))),
+            "{:?}",
+            mode
+        );
+    }
+}
+
+#[test]
+fn bind_passes_plugin_strings() {
+    let mock = MockBackend::new();
+    mock.on("KQHouTai", |_| Ok(Value::I32(1)));
+    mock.on("GBHouTai", |_| Ok(Value::I32(1)));
+    let aojia = AoJia::with_backend(mock.clone());
+    let mode = BindMode::new(ScreenMode::Dx2, KeyboardMode::Windows, MouseMode::Dx)
+        .flags(BindFlags::ACTIVE_API | BindFlags::LOCK_MOUSE_POSITION);
+    drop(aojia.bind(100, mode).unwrap());
+
+    let calls = mock.calls();
+    let expected: Vec<RecordedArg> = [
+        Value::I32(100),
+        "dx2".into(),
+        "windows".into(),
+        "dx".into(),
+        "dx.public.active.api|dx.mouse.position.lock.api".into(),
+        Value::I32(0),
+    ]
+    .into_iter()
+    .map(RecordedArg::In)
+    .collect();
+    assert_eq!(calls[0].args, expected);
+}
+
+#[test]
+fn bind_rejects_invalid_mode() {
+    let mock = MockBackend::new();
+    let aojia = AoJia::with_backend(mock.clone());
+    let mode = BindMode::default().flags(BindFlags::HIDE_DLL);
+    assert!(aojia.bind(100, mode).is_err());
+    assert!(mock.calls().is_empty());
+}
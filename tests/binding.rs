@@ -0,0 +1,76 @@
+use aojia::binding::{KeyboardMode, MouseMode, ScreenMode};
+use aojia::{AoJia, BindMode, Binding, Error, MockBackend, Value};
+
+fn mock() -> MockBackend {
+    MockBackend::succeeding(&["KQHouTai", "GBHouTai"])
+}
+
+fn mode() -> BindMode {
+    BindMode::new(ScreenMode::Gdi, KeyboardMode::Windows, MouseMode::Windows)
+}
+
+#[test]
+fn unbinds_on_drop() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    {
+        let guard = aojia.bind(100, mode()).unwrap();
+        assert_eq!(guard.hwnd(), 100);
+        assert_eq!(guard.mode(), &mode());
+        assert_eq!(
+            aojia.binding(),
+            Some(Binding {
+                hwnd: 100,
+                mode: mode()
+            })
+        );
+    }
+    assert_eq!(aojia.binding(), None);
+    assert_eq!(mock.call_names(), ["KQHouTai", "GBHouTai"]);
+}
+
+#[test]
+fn unbinds_on_early_return() {
+    fn work(aojia: &AoJia) -> aojia::Result<()> {
+        let _guard = aojia.bind(100, mode())?;
+        Err(Error::Cancelled)
+    }
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    assert!(work(&aojia).is_err());
+    assert_eq!(aojia.binding(), None);
+    assert_eq!(mock.call_names(), ["KQHouTai", "GBHouTai"]);
+}
+
+#[test]
+fn rejects_double_binding() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let guard = aojia.bind(100, mode()).unwrap();
+    assert!(matches!(
+        aojia.bind(200, mode()),
+        Err(Error::AlreadyBound(100))
+    ));
+    guard.unbind().unwrap();
+    assert_eq!(aojia.bind(200, mode()).unwrap().hwnd(), 200);
+    assert_eq!(
+        mock.call_names(),
+        ["KQHouTai", "GBHouTai", "KQHouTai", "GBHouTai"]
+    );
+}
+
+#[test]
+fn failed_bind_leaves_unbound() {
+    let mock = MockBackend::new();
+    mock.on("KQHouTai", |_| Ok(Value::I32(0)));
+    let aojia = AoJia::with_backend(mock.clone());
+    assert!(matches!(
+        aojia.bind(100, mode()),
+        Err(Error::Failed {
+            fun: "KQHouTai",
+            ret: 0
+        })
+    ));
+    assert_eq!(aojia.binding(), None);
+    assert_eq!(mock.call_names(), ["KQHouTai"]);
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::binding::{
+    BindingEvent, BindingSupervisor, KeyboardMode, MouseMode, RebindReason, ScreenMode,
+    SupervisorOptions,
+};
+use aojia::{AoJia, BindMode, Error, MockBackend, MockClock, Value, WindowQuery};
+use common::in_i32;
+
+/// 当前存在的窗口句柄，0 表示没有窗口
+type Current = Arc<Mutex<i32>>;
+
+fn mock_window(current: &Current) -> MockBackend {
+    let mock = MockBackend::new();
+    let c = current.clone();
+    mock.on("FindWindow", move |_| Ok(Value::I32(*c.lock().unwrap())));
+    let c = current.clone();
+    mock.on("GetClientSize", move |args| {
+        let hwnd = in_i32(args, 0);
+        Ok(Value::I32((hwnd != 0 && hwnd == *c.lock().unwrap()) as i32))
+    });
+    mock.on("KQHouTai", |_| Ok(Value::I32(1)));
+    mock.on("GBHouTai", |_| Ok(Value::I32(1)));
+    mock
+}
+
+fn mode() -> BindMode {
+    BindMode::new(ScreenMode::Dx2, KeyboardMode::Windows, MouseMode::Windows)
+}
+
+fn query() -> WindowQuery {
+    WindowQuery::new().class("GameWnd")
+}
+
+/// KQHouTai 调用时传入的句柄
+fn bound_hwnds(mock: &MockBackend) -> Vec<Value> {
+    mock.calls()
+        .into_iter()
+        .filter(|c| c.name == "KQHouTai")
+        .map(|c| match &c.args[0] {
+            RecordedArg::In(v) => v.clone(),
+            other => panic!("{:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn rebinds_recreated_window() {
+    let current = Current::new(Mutex::new(100));
+    let mock = mock_window(&current);
+    let aojia = AoJia::with_backend(mock.clone());
+    let mut supervisor =
+        BindingSupervisor::new(&aojia, query(), mode(), SupervisorOptions::default()).unwrap();
+    let events = supervisor.subscribe();
+    assert_eq!(supervisor.hwnd(), Some(100));
+    assert_eq!(supervisor.check().unwrap(), None);
+
+    *current.lock().unwrap() = 200;
+    let expected = BindingEvent::Rebound {
+        old: Some(100),
+        new: 200,
+        reason: RebindReason::WindowGone,
+    };
+    assert_eq!(supervisor.check().unwrap(), Some(expected.clone()));
+    assert_eq!(events.try_recv().unwrap(), expected);
+    assert_eq!(supervisor.hwnd(), Some(200));
+    assert_eq!(aojia.binding().unwrap().mode, mode());
+    assert_eq!(bound_hwnds(&mock), [Value::I32(100), Value::I32(200)]);
+}
+
+#[test]
+fn retries_until_window_returns() {
+    let current = Current::new(Mutex::new(100));
+    let aojia = AoJia::with_backend(mock_window(&current));
+    let mut supervisor =
+        BindingSupervisor::new(&aojia, query(), mode(), SupervisorOptions::default()).unwrap();
+
+    *current.lock().unwrap() = 0;
+    assert!(matches!(
+        supervisor.check().unwrap(),
+        Some(BindingEvent::RebindFailed {
+            old: Some(100),
+            reason: RebindReason::WindowGone,
+            ..
+        })
+    ));
+    assert_eq!(supervisor.hwnd(), None);
+    assert_eq!(aojia.binding(), None);
+
+    *current.lock().unwrap() = 300;
+    assert_eq!(
+        supervisor.check().unwrap(),
+        Some(BindingEvent::Rebound {
+            old: None,
+            new: 300,
+            reason: RebindReason::WindowGone
+        })
+    );
+}
+
+#[test]
+fn rebinds_after_repeated_failures() {
+    let current = Current::new(Mutex::new(100));
+    let aojia = AoJia::with_backend(mock_window(&current));
+    let clock = MockClock::new();
+    let options = SupervisorOptions::new(Duration::from_secs(60), 2).clock(clock.clone());
+    let mut supervisor = BindingSupervisor::new(&aojia, query(), mode(), options).unwrap();
+    let events = supervisor.subscribe();
+
+    let fail = |_: &AoJia| -> aojia::Result<()> { Err(Error::Cancelled) };
+    assert!(supervisor.run(fail).is_err());
+    assert!(supervisor.run(|_| Ok(())).is_ok());
+    assert!(supervisor.run(fail).is_err());
+    assert!(events.try_recv().is_err());
+    assert!(supervisor.run(fail).is_err());
+    assert_eq!(
+        events.try_recv().unwrap(),
+        BindingEvent::Rebound {
+            old: Some(100),
+            new: 100,
+            reason: RebindReason::OperationsFailed(2)
+        }
+    );
+}
+
+#[test]
+fn run_checks_after_interval() {
+    let current = Current::new(Mutex::new(100));
+    let aojia = AoJia::with_backend(mock_window(&current));
+    let clock = MockClock::new();
+    let options = SupervisorOptions::new(Duration::from_secs(1), 3).clock(clock.clone());
+    let mut supervisor = BindingSupervisor::new(&aojia, query(), mode(), options).unwrap();
+
+    *current.lock().unwrap() = 200;
+    supervisor.run(|_| Ok(())).unwrap();
+    assert_eq!(supervisor.hwnd(), Some(100));
+    clock.advance(Duration::from_secs(1));
+    supervisor.run(|_| Ok(())).unwrap();
+    assert_eq!(supervisor.hwnd(), Some(200));
+}
+
+#[test]
+fn new_fails_without_window() {
+    let current = Current::new(Mutex::new(0));
+    let aojia = AoJia::with_backend(mock_window(&current));
+    assert!(matches!(
+        BindingSupervisor::new(&aojia, query(), mode(), SupervisorOptions::default()),
+        Err(Error::Failed {
+            fun: "FindWindow",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn drop_unbinds() {
+    let current = Current::new(Mutex::new(100));
+    let mock = mock_window(&current);
+    let aojia = AoJia::with_backend(mock.clone());
+    drop(BindingSupervisor::new(&aojia, query(), mode(), SupervisorOptions::default()).unwrap());
+    assert_eq!(aojia.binding(), None);
+    assert_eq!(mock.calls().last().unwrap().name, "GBHouTai");
+}
@@ -0,0 +1,121 @@
+use aojia::Error;
+use aojia::pic::Bitmap;
+
+/// 2x2 的测试图像，按行从上到下
+const PIXELS: [u32; 4] = [0xFF0000, 0x00FF00, 0x0000FF, 0x123456];
+
+/// 构造 BMP 文件，`masks` 为 None 时为 BI_RGB，否则为 32 位 BI_BITFIELDS
+fn bmp(bpp: u16, top_down: bool, masks: Option<[u32; 3]>) -> Vec<u8> {
+    let (width, height) = (2u32, 2u32);
+    let stride = (width as usize * bpp as usize).div_ceil(32) * 4;
+    let offset = 54 + if masks.is_some() { 12 } else { 0 };
+    let mut data = Vec::new();
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&((offset + stride * height as usize) as u32).to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&(offset as u32).to_le_bytes());
+    data.extend_from_slice(&40u32.to_le_bytes());
+    data.extend_from_slice(&(width as i32).to_le_bytes());
+    let h = if top_down {
+        -(height as i32)
+    } else {
+        height as i32
+    };
+    data.extend_from_slice(&h.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&bpp.to_le_bytes());
+    data.extend_from_slice(&(if masks.is_some() { 3u32 } else { 0 }).to_le_bytes());
+    data.extend_from_slice(&[0; 20]);
+    if let Some(masks) = masks {
+        for mask in masks {
+            data.extend_from_slice(&mask.to_le_bytes());
+        }
+    }
+    for row in 0..height {
+        let y = if top_down { row } else { height - 1 - row };
+        let start = data.len();
+        for x in 0..width {
+            let c = PIXELS[(y * width + x) as usize];
+            let (r, g, b) = ((c >> 16) & 0xFF, (c >> 8) & 0xFF, c & 0xFF);
+            match (bpp, masks) {
+                (24, _) => data.extend_from_slice(&[b as u8, g as u8, r as u8]),
+                (32, None) => data.extend_from_slice(&[b as u8, g as u8, r as u8, 0xFF]),
+                // 按 RGBA 顺序排列的掩码
+                (32, Some(_)) => data.extend_from_slice(&[r as u8, g as u8, b as u8, 0xFF]),
+                _ => unreachable!(),
+            }
+        }
+        data.resize(start + stride, 0);
+    }
+    data
+}
+
+fn pixels(bitmap: &Bitmap) -> Vec<u32> {
+    (0..bitmap.height())
+        .flat_map(|y| (0..bitmap.width()).map(move |x| bitmap.pixel(x, y)))
+        .collect()
+}
+
+#[test]
+fn decode_24_and_32_bit() {
+    let rgba = Some([0x0000FF, 0x00FF00, 0xFF0000]);
+    for (bpp, masks) in [(24, None), (32, None), (32, rgba)] {
+        for top_down in [false, true] {
+            let bitmap = Bitmap::decode(&bmp(bpp, top_down, masks)).unwrap();
+            assert_eq!((bitmap.width(), bitmap.height()), (2, 2));
+            assert_eq!(
+                pixels(&bitmap),
+                PIXELS,
+                "{} 位 {:?} {}",
+                bpp,
+                masks,
+                top_down
+            );
+        }
+    }
+}
+
+#[test]
+fn encode_round_trip() {
+    let bitmap = Bitmap::from_pixels(2, 2, PIXELS.to_vec()).unwrap();
+    assert_eq!(bitmap.encode(), bmp(24, false, None));
+    assert_eq!(Bitmap::decode(&bitmap.encode()).unwrap(), bitmap);
+}
+
+#[test]
+fn invalid_files() {
+    let data = bmp(24, false, None);
+    assert!(matches!(Bitmap::decode(b"PNG"), Err(Error::Image(_))));
+    assert!(matches!(Bitmap::decode(&data[..30]), Err(Error::Image(_))));
+    assert!(matches!(
+        Bitmap::decode(&data[..data.len() - 1]),
+        Err(Error::Image(_))
+    ));
+
+    let mut bpp16 = data.clone();
+    bpp16[28] = 16;
+    assert!(matches!(Bitmap::decode(&bpp16), Err(Error::Image(_))));
+    let mut rle = data.clone();
+    rle[30] = 1;
+    assert!(matches!(Bitmap::decode(&rle), Err(Error::Image(_))));
+}
+
+/// 文件头中的尺寸过大时返回错误而不是溢出
+#[test]
+fn hostile_header_does_not_overflow() {
+    for (width, height, bpp) in [
+        (i32::MAX, i32::MAX, 32u16),
+        (i32::MAX, i32::MIN + 1, 24),
+        (0x10000, 0x10000, 24),
+    ] {
+        let mut data = bmp(24, false, None);
+        data[18..22].copy_from_slice(&width.to_le_bytes());
+        data[22..26].copy_from_slice(&height.to_le_bytes());
+        data[28..30].copy_from_slice(&bpp.to_le_bytes());
+        match Bitmap::decode(&data) {
+            Err(Error::Image(msg)) => assert!(msg.contains("过大"), "{}", msg),
+            other => panic!("{}x{}: {:?}", width, height, other),
+        }
+    }
+    assert!(Bitmap::from_pixels(u32::MAX, 2, vec![0; 2]).is_err());
+}
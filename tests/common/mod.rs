@@ -0,0 +1,53 @@
+//! 集成测试共用的模拟后端和参数辅助函数，每个测试只用到其中一部分
+
+#![allow(dead_code)]
+
+use aojia::backend::mock::RecordedArg;
+use aojia::{Arg, MockBackend, Value};
+
+/// CreateWindows 返回 `hwnd`，设置字体、绘制文字和关闭窗口都成功
+pub fn overlay_mock(hwnd: i32) -> MockBackend {
+    let mock = MockBackend::succeeding(&["SetFont", "SetTextD", "DrawTextD", "SetWindowState"]);
+    mock.on("CreateWindows", move |_| Ok(Value::I32(hwnd)));
+    mock
+}
+
+/// 第 `i` 个输入参数的值
+pub fn input(args: &[Arg<'_>], i: usize) -> Value {
+    match &args[i] {
+        Arg::In(value) => value.clone(),
+        Arg::Out(_) => panic!("第 {} 个参数不是输入参数", i + 1),
+    }
+}
+
+/// 第 `i` 个输入参数转换为整数
+pub fn in_i32(args: &[Arg<'_>], i: usize) -> i32 {
+    input(args, i).to_i32().unwrap()
+}
+
+/// 给第 `i` 个输出参数赋值
+pub fn set_out(args: &mut [Arg<'_>], i: usize, value: impl Into<Value>) {
+    match &mut args[i] {
+        Arg::Out(out) => **out = value.into(),
+        Arg::In(_) => panic!("第 {} 个参数不是输出参数", i + 1),
+    }
+}
+
+/// 名为 `name` 的调用次数
+pub fn count_calls(mock: &MockBackend, name: &str) -> usize {
+    mock.call_names().iter().filter(|n| *n == name).count()
+}
+
+/// 每次 DrawTextD 绘制的文字和颜色
+pub fn drawn(mock: &MockBackend) -> Vec<(String, String)> {
+    mock.calls()
+        .into_iter()
+        .filter(|c| c.name == "DrawTextD")
+        .map(|c| match (&c.args[1], &c.args[2]) {
+            (RecordedArg::In(text), RecordedArg::In(color)) => {
+                (text.to_string().unwrap(), color.to_string().unwrap())
+            }
+            args => panic!("{:?}", args),
+        })
+        .collect()
+}
@@ -0,0 +1,301 @@
+mod common;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use aojia::backend::mock::RecordedArg;
+use aojia::compress::{CompressionFormat, CompressionLevel};
+use aojia::{AoJia, Error, MockBackend, Value};
+use common::input;
+
+/// 压缩时在内容前加上格式和级别，解压时检查并去掉
+fn mock() -> MockBackend {
+    let mock = MockBackend::new();
+    mock.on("CompressFile", |args| {
+        let (sf, df) = (input(args, 0).to_string()?, input(args, 1).to_string()?);
+        let header = format!("{}:{}:", input(args, 2).to_i32()?, input(args, 3).to_i32()?);
+        let Ok(data) = fs::read(&sf) else {
+            return Ok(Value::I32(-1));
+        };
+        let ret = match fs::write(&df, [header.as_bytes(), &data].concat()) {
+            Ok(()) => 1,
+            Err(_) => -2,
+        };
+        Ok(Value::I32(ret))
+    });
+    mock.on("UnCompressFile", |args| {
+        let (sf, df) = (input(args, 0).to_string()?, input(args, 1).to_string()?);
+        let ty = input(args, 2).to_i32()?;
+        let data = fs::read(&sf).unwrap();
+        let Some(rest) = data.strip_prefix(format!("{}:", ty).as_bytes()) else {
+            return Ok(Value::I32(-3));
+        };
+        let start = rest.iter().position(|&b| b == b':').unwrap() + 1;
+        fs::write(&df, &rest[start..]).unwrap();
+        Ok(Value::I32(1))
+    });
+    mock
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aojia-compress-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(path: &Path, data: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, data).unwrap();
+}
+
+#[test]
+fn enum_encoding() {
+    for ty in 0..=2 {
+        assert_eq!(i32::from(CompressionFormat::try_from(ty).unwrap()), ty);
+    }
+    assert!(CompressionFormat::try_from(3).is_err());
+    for level in 0..=9 {
+        assert_eq!(i32::from(CompressionLevel::try_from(level).unwrap()), level);
+    }
+    assert_eq!(
+        CompressionLevel::try_from(9).unwrap(),
+        CompressionLevel::Best
+    );
+    assert!(CompressionLevel::try_from(10).is_err());
+    assert!(CompressionLevel::try_from(-1).is_err());
+    assert!(CompressionLevel::Level(10).validate().is_err());
+    assert_eq!(i32::from(CompressionLevel::default()), 6);
+}
+
+#[test]
+fn level_equality_uses_effective_level() {
+    assert_eq!(CompressionLevel::Level(6), CompressionLevel::Default);
+    assert_eq!(
+        CompressionLevel::Level(6),
+        CompressionLevel::try_from(6).unwrap()
+    );
+    assert_eq!(CompressionLevel::Level(0), CompressionLevel::Store);
+    assert_eq!(CompressionLevel::Level(9), CompressionLevel::Best);
+    assert_ne!(CompressionLevel::Level(5), CompressionLevel::Default);
+
+    let levels: std::collections::HashSet<CompressionLevel> = [
+        CompressionLevel::Level(1),
+        CompressionLevel::Fastest,
+        CompressionLevel::Level(6),
+        CompressionLevel::Default,
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(levels.len(), 2);
+}
+
+#[test]
+fn compress_and_uncompress_file() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let dir = temp_dir("file");
+    write(&dir.join("a.txt"), "hello");
+
+    aojia
+        .compress_file(
+            dir.join("a.txt"),
+            dir.join("a.gz"),
+            CompressionFormat::Gzip,
+            CompressionLevel::Best,
+        )
+        .unwrap();
+    assert_eq!(fs::read_to_string(dir.join("a.gz")).unwrap(), "1:9:hello");
+    aojia
+        .uncompress_file(dir.join("a.gz"), dir.join("b.txt"), CompressionFormat::Gzip)
+        .unwrap();
+    assert_eq!(fs::read_to_string(dir.join("b.txt")).unwrap(), "hello");
+
+    // 格式不符
+    let err = aojia
+        .uncompress_file(dir.join("a.gz"), dir.join("c.txt"), CompressionFormat::Zip)
+        .unwrap_err();
+    assert!(matches!(&err, Error::Io(e) if e.kind() == io::ErrorKind::InvalidData));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn paths_checked_before_calling() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let dir = temp_dir("check");
+    write(&dir.join("a.txt"), "hello");
+    let level = CompressionLevel::Default;
+
+    for (src, dst) in [
+        (dir.join("missing.txt"), dir.join("a.zlib")),
+        (dir.clone(), dir.join("a.zlib")),
+        (dir.join("a.txt"), dir.join("missing").join("a.zlib")),
+    ] {
+        let err = aojia
+            .compress_file(&src, &dst, CompressionFormat::Zlib, level)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)), "{:?}", err);
+    }
+    assert!(
+        aojia
+            .compress_file(
+                dir.join("a.txt"),
+                dir.join("a.zlib"),
+                CompressionFormat::Zlib,
+                CompressionLevel::Level(12),
+            )
+            .is_err()
+    );
+    assert!(mock.calls().is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn return_codes_mapped_to_errors() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let dir = temp_dir("codes");
+    write(&dir.join("a.txt"), "hello");
+    let compress = || {
+        aojia.compress_file(
+            dir.join("a.txt"),
+            dir.join("a.zip"),
+            CompressionFormat::Zip,
+            CompressionLevel::Fastest,
+        )
+    };
+
+    for (ret, kind) in [
+        (-1, io::ErrorKind::NotFound),
+        (-2, io::ErrorKind::PermissionDenied),
+        (-3, io::ErrorKind::InvalidData),
+    ] {
+        mock.on("CompressFile", move |_| Ok(Value::I32(ret)));
+        assert!(matches!(compress(), Err(Error::Io(e)) if e.kind() == kind));
+    }
+    for ret in [0, -9] {
+        mock.on("CompressFile", move |_| Ok(Value::I32(ret)));
+        assert!(matches!(
+            compress(),
+            Err(Error::Failed { fun: "CompressFile", ret: r }) if r == ret
+        ));
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// 按包文件的格式写入 `(路径, 内容)`
+fn pack(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut data = b"AJPK".to_vec();
+    data.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (name, content) in entries {
+        data.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        data.extend_from_slice(name.as_bytes());
+        data.extend_from_slice(&(content.len() as u64).to_le_bytes());
+        data.extend_from_slice(content.as_bytes());
+    }
+    data
+}
+
+#[test]
+fn pack_and_unpack_directory() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let dir = temp_dir("dir");
+    let (src, packed, unpacked) = (dir.join("src"), dir.join("src.zlib"), dir.join("unpacked"));
+    write(&src.join("a.txt"), "a");
+    write(&src.join("sub").join("b.bmp"), "b");
+    write(&src.join("sub").join("deep").join("c"), "c");
+
+    let files = aojia
+        .pack_dir(
+            &src,
+            &packed,
+            CompressionFormat::Zlib,
+            CompressionLevel::Store,
+        )
+        .unwrap();
+    let rel = [
+        Path::new("a.txt").to_path_buf(),
+        Path::new("sub").join("b.bmp"),
+        Path::new("sub").join("deep").join("c"),
+    ];
+    assert_eq!(files, rel);
+    // 整个目录压缩为一个文件，中间的包文件已删除
+    let calls = mock.calls();
+    assert_eq!(mock.call_names(), ["CompressFile"]);
+    let RecordedArg::In(tmp) = &calls[0].args[0] else {
+        unreachable!()
+    };
+    assert!(!Path::new(&tmp.to_string().unwrap()).exists());
+    let expected = [
+        b"0:0:".to_vec(),
+        pack(&[("a.txt", "a"), ("sub/b.bmp", "b"), ("sub/deep/c", "c")]),
+    ]
+    .concat();
+    assert_eq!(fs::read(&packed).unwrap(), expected);
+
+    let files = aojia
+        .unpack_dir(&packed, &unpacked, CompressionFormat::Zlib)
+        .unwrap();
+    assert_eq!(files, rel.map(|rel| unpacked.join(rel)));
+    assert_eq!(fs::read_to_string(unpacked.join("a.txt")).unwrap(), "a");
+    assert_eq!(
+        fs::read_to_string(unpacked.join("sub").join("b.bmp")).unwrap(),
+        "b"
+    );
+    assert_eq!(
+        fs::read_to_string(unpacked.join("sub").join("deep").join("c")).unwrap(),
+        "c"
+    );
+
+    assert!(matches!(
+        aojia.pack_dir(
+            dir.join("none"),
+            dir.join("none.zlib"),
+            CompressionFormat::Zlib,
+            CompressionLevel::Best
+        ),
+        Err(Error::InvalidArgument(_))
+    ));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unpack_rejects_invalid_packs() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let dir = temp_dir("invalid");
+    let out = dir.join("out");
+    let mut truncated = pack(&[("a.txt", "hello")]);
+    truncated.pop();
+
+    for (name, data) in [
+        ("plain", b"hello".to_vec()),
+        ("truncated", truncated),
+        ("parent", pack(&[("a.txt", "a"), ("../evil.txt", "x")])),
+        ("empty", pack(&[("sub//a.txt", "a")])),
+        ("backslash", pack(&[("sub\\..\\..\\evil.txt", "x")])),
+    ] {
+        let raw = dir.join(name);
+        let zlib = dir.join(format!("{}.zlib", name));
+        fs::write(&raw, data).unwrap();
+        aojia
+            .compress_file(&raw, &zlib, CompressionFormat::Zlib, CompressionLevel::Best)
+            .unwrap();
+        let err = aojia
+            .unpack_dir(&zlib, &out, CompressionFormat::Zlib)
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::Io(e) if e.kind() == io::ErrorKind::InvalidData),
+            "{}: {:?}",
+            name,
+            err
+        );
+    }
+    // 检查通过之前不写入任何文件
+    assert!(!out.exists());
+    assert!(!dir.join("evil.txt").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
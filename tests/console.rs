@@ -0,0 +1,119 @@
+mod common;
+
+use aojia::overlay::{Color, Font, LogConsole, Overlay, TextArea, wrap};
+use aojia::{AoJia, Rect};
+use common::{drawn, overlay_mock};
+use log::{Level, LevelFilter};
+
+#[test]
+fn wrap_by_font_width() {
+    let font = Font::new("宋体", 12);
+    assert_eq!(wrap("abcdefgh", &font, 24), ["abcd", "efgh"]);
+    assert_eq!(wrap("血量不足ab", &font, 30), ["血量", "不足a", "b"]);
+    assert_eq!(wrap("", &font, 30), [""]);
+    // 宽度不足一个字符时每行一个字符
+    assert_eq!(wrap("血量", &font, 5), ["血", "量"]);
+}
+
+#[test]
+fn ring_buffer_keeps_latest() {
+    let console = LogConsole::new(3);
+    for i in 0..5 {
+        console.push(Level::Info, &format!("第 {} 行", i));
+    }
+    console.push(Level::Warn, "a\nb");
+    let texts: Vec<String> = console.lines().into_iter().map(|l| l.text).collect();
+    assert_eq!(texts, ["第 4 行", "a", "b"]);
+    assert_eq!(console.lines()[1].level, Level::Warn);
+    console.clear();
+    assert!(console.lines().is_empty());
+}
+
+#[test]
+fn empty_messages_are_kept_as_blank_lines() {
+    let mock = overlay_mock(5);
+    let aojia = AoJia::with_backend(mock.clone());
+    let overlay = Overlay::new(&aojia, Rect::from_size(0, 0, 48, 100)).unwrap();
+    let console = LogConsole::new(10);
+    console.push(Level::Info, "a");
+    console.push(Level::Info, "");
+    console.push(Level::Info, "b\n\nc");
+    let texts: Vec<String> = console.lines().into_iter().map(|l| l.text).collect();
+    assert_eq!(texts, ["a", "", "b", "", "c"]);
+
+    console.render(&overlay).unwrap();
+    let texts: Vec<String> = drawn(&mock).into_iter().map(|(t, _)| t).collect();
+    assert_eq!(texts, ["a", "", "b", "", "c"]);
+}
+
+#[test]
+fn render_new_lines_with_level_colors() {
+    let mock = overlay_mock(5);
+    let aojia = AoJia::with_backend(mock.clone());
+    let overlay = Overlay::new(&aojia, Rect::from_size(0, 0, 48, 100)).unwrap();
+    let console = LogConsole::new(10);
+    console.set_color(Level::Debug, Color::BLUE);
+
+    console.push(Level::Info, "开始");
+    console.push(Level::Error, "找不到图片");
+    console.render(&overlay).unwrap();
+    console.push(Level::Debug, "x");
+    console.render(&overlay).unwrap();
+    console.render(&overlay).unwrap();
+
+    assert_eq!(
+        drawn(&mock),
+        [
+            ("开始".to_string(), "FFFFFF".to_string()),
+            ("找不到图".to_string(), "FF0000".to_string()),
+            ("片".to_string(), "FF0000".to_string()),
+            ("x".to_string(), "0000FF".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn redraw_shows_last_rows() {
+    let mock = overlay_mock(5);
+    let aojia = AoJia::with_backend(mock.clone());
+    let mut overlay = Overlay::new(&aojia, Rect::from_size(0, 0, 100, 100)).unwrap();
+    overlay
+        .set_text_area(TextArea::new(Rect::from_size(0, 0, 24, 100), 3))
+        .unwrap();
+    let console = LogConsole::new(10);
+    console.push(Level::Info, "one");
+    console.push(Level::Warn, "abcdefg");
+    mock.clear_calls();
+
+    console.redraw(&overlay).unwrap();
+    assert_eq!(mock.calls()[0].name, "SetTextD");
+    let texts: Vec<String> = drawn(&mock).into_iter().map(|(t, _)| t).collect();
+    assert_eq!(texts, ["one", "abcd", "efg"]);
+
+    // 重画后没有待绘制的行
+    mock.clear_calls();
+    console.render(&overlay).unwrap();
+    assert!(drawn(&mock).is_empty());
+}
+
+#[test]
+fn install_as_logger() {
+    let console = LogConsole::new(10);
+    console.install(LevelFilter::Info).unwrap();
+    log::info!("连接成功");
+    log::debug!("不会记录");
+    log::warn!("掉线 {} 次", 2);
+    let lines: Vec<(Level, String)> = console
+        .lines()
+        .into_iter()
+        .map(|l| (l.level, l.text))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            (Level::Info, "连接成功".to_string()),
+            (Level::Warn, "掉线 2 次".to_string())
+        ]
+    );
+    assert!(LogConsole::new(1).install(LevelFilter::Info).is_err());
+}
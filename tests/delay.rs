@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::delay::Distribution;
+use aojia::input::MouseButton;
+use aojia::{AoJia, DelayPolicy, DelayScheduler, MockBackend, MockClock, Rng};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn samples(policy: DelayPolicy, n: usize) -> Vec<f64> {
+    let mut rng = Rng::new(1);
+    (0..n)
+        .map(|_| policy.sample(&mut rng).as_secs_f64() * 1000.0)
+        .collect()
+}
+
+fn median(mut v: Vec<f64>) -> f64 {
+    v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    v[v.len() / 2]
+}
+
+#[test]
+fn fixed_and_speed() {
+    let mut rng = Rng::new(0);
+    assert_eq!(DelayPolicy::fixed(ms(100)).sample(&mut rng), ms(100));
+    assert_eq!(
+        DelayPolicy::fixed(ms(100)).speed(2.0).sample(&mut rng),
+        ms(50)
+    );
+}
+
+#[test]
+fn uniform_within_range() {
+    let v = samples(DelayPolicy::uniform(ms(50), ms(150)), 2000);
+    assert!(v.iter().all(|d| (50.0..150.0).contains(d)));
+    let mean = v.iter().sum::<f64>() / v.len() as f64;
+    assert!((mean - 100.0).abs() < 5.0, "{}", mean);
+}
+
+#[test]
+fn normal_clamped() {
+    let policy = DelayPolicy::normal(ms(100), ms(40)).clamp(ms(60), ms(140));
+    let v = samples(policy, 2000);
+    assert!(v.iter().all(|d| (60.0..=140.0).contains(d)));
+    assert!((median(v) - 100.0).abs() < 5.0);
+}
+
+#[test]
+fn log_normal_median() {
+    let v = samples(DelayPolicy::log_normal(ms(200), 0.5), 4000);
+    assert!(v.iter().all(|d| *d > 0.0));
+    assert!((median(v.clone()) - 200.0).abs() < 15.0);
+    // 右偏：均值大于中位数
+    assert!(v.iter().sum::<f64>() / v.len() as f64 > 200.0);
+}
+
+#[test]
+fn seeded_is_reproducible() {
+    let policy = DelayPolicy::log_normal(ms(100), 0.8);
+    let mut a = DelayScheduler::new(policy).unwrap().seed(5);
+    let mut b = DelayScheduler::new(policy).unwrap().seed(5);
+    for _ in 0..50 {
+        assert_eq!(a.next_delay(), b.next_delay());
+    }
+}
+
+#[test]
+fn invalid_policies() {
+    let cases = [
+        DelayPolicy::fixed(ms(1)).clamp(ms(10), ms(5)),
+        DelayPolicy::fixed(ms(1)).speed(0.0),
+        DelayPolicy::fixed(ms(1)).speed(f64::NAN),
+        DelayPolicy::uniform(ms(10), ms(5)),
+        DelayPolicy::new(Distribution::LogNormal {
+            median: ms(10),
+            sigma: -1.0,
+        }),
+    ];
+    for policy in cases {
+        assert!(DelayScheduler::new(policy).is_err(), "{:?}", policy);
+    }
+}
+
+#[test]
+fn helpers_wait_with_clock() {
+    let mock = MockBackend::succeeding(&["LeftClick", "KeyPress"]);
+    let aojia = AoJia::with_backend(mock);
+    let clock = MockClock::new();
+    aojia.set_delay(Some(
+        DelayScheduler::new(DelayPolicy::fixed(ms(80)))
+            .unwrap()
+            .clock(clock.clone()),
+    ));
+    aojia.click(MouseButton::Left).unwrap();
+    aojia.key_press(aojia::input::Key::A).unwrap();
+    assert_eq!(clock.elapsed(), ms(160));
+
+    aojia.set_delay(None);
+    aojia.click(MouseButton::Left).unwrap();
+    assert_eq!(clock.elapsed(), ms(160));
+}
+
+#[test]
+fn delegates_to_yanshi() {
+    let mock = MockBackend::succeeding(&["LeftClick", "YanShi"]);
+    let aojia = AoJia::with_backend(mock.clone());
+    let clock = MockClock::new();
+    aojia.set_delay(Some(
+        DelayScheduler::new(DelayPolicy::fixed(ms(120)))
+            .unwrap()
+            .clock(clock.clone())
+            .use_yanshi(true),
+    ));
+    aojia.click(MouseButton::Left).unwrap();
+    let calls = mock.calls();
+    assert_eq!(calls[1].name, "YanShi");
+    assert_eq!(
+        calls[1].args,
+        [RecordedArg::In(120.into()), RecordedArg::In(120.into())]
+    );
+    assert_eq!(clock.elapsed(), Duration::ZERO);
+}
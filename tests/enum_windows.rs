@@ -0,0 +1,94 @@
+mod common;
+
+use aojia::window::{WindowInfo, WindowQuery, parse_hwnd_list};
+use aojia::{AoJia, Error, MockBackend, Value};
+use common::{in_i32, set_out};
+
+#[test]
+fn parse_hwnd_lists() {
+    assert_eq!(parse_hwnd_list("100|200|300").unwrap(), vec![100, 200, 300]);
+    assert_eq!(parse_hwnd_list("100,200").unwrap(), vec![100, 200]);
+    assert_eq!(parse_hwnd_list(" 100 | 200 |").unwrap(), vec![100, 200]);
+    assert_eq!(parse_hwnd_list("").unwrap(), vec![]);
+}
+
+#[test]
+fn parse_bad_hwnd() {
+    assert!(matches!(parse_hwnd_list("100|abc"), Err(Error::Convert(_))));
+}
+
+/// 三个窗口，其中 300 在枚举后已关闭
+fn mock_windows() -> MockBackend {
+    let mock = MockBackend::new();
+    mock.on("EnumWindow", |_| Ok("100|200|300".into()));
+    mock.on("GetClientSize", |args| {
+        let hwnd = in_i32(args, 0);
+        if hwnd == 300 {
+            return Ok(Value::I32(0));
+        }
+        set_out(args, 1, hwnd * 8);
+        set_out(args, 2, hwnd * 6);
+        Ok(Value::I32(1))
+    });
+    mock.on("GetWindowTitle", |args| {
+        Ok(format!("客户端 {}", in_i32(args, 0)).into())
+    });
+    mock.on("GetWindowClass", |_| Ok("GameWnd".into()));
+    mock.on("GetWindowProcessId", |args| {
+        Ok(Value::I32(in_i32(args, 0) + 1))
+    });
+    mock
+}
+
+#[test]
+fn enum_windows_passes_query() {
+    let mock = mock_windows();
+    let aojia = AoJia::with_backend(mock.clone());
+    let hwnds = aojia
+        .enum_windows(&WindowQuery::new().class("GameWnd").visible_only())
+        .unwrap();
+    assert_eq!(hwnds, vec![100, 200, 300]);
+
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].name, "EnumWindow");
+}
+
+#[test]
+fn enum_window_infos_skips_closed() {
+    let aojia = AoJia::with_backend(mock_windows());
+    let infos = aojia
+        .enum_window_infos(&WindowQuery::new().class("GameWnd"))
+        .unwrap();
+    let info = |hwnd: i32| WindowInfo {
+        hwnd,
+        title: format!("客户端 {}", hwnd),
+        class: "GameWnd".to_string(),
+        pid: hwnd + 1,
+        width: hwnd * 8,
+        height: hwnd * 6,
+    };
+    assert_eq!(infos, vec![info(100), info(200)]);
+}
+
+#[test]
+fn window_info_of_closed_window() {
+    let aojia = AoJia::with_backend(mock_windows());
+    assert!(matches!(
+        aojia.window_info(300),
+        Err(Error::WindowGone(300))
+    ));
+}
+
+#[test]
+fn enum_window_infos_propagates_other_errors() {
+    let mock = mock_windows();
+    mock.on("GetWindowClass", |_| {
+        Err(Error::InvalidArgument("类名不可读".to_string()))
+    });
+    let aojia = AoJia::with_backend(mock);
+    assert!(matches!(
+        aojia.enum_window_infos(&WindowQuery::new().class("GameWnd")),
+        Err(Error::InvalidArgument(_))
+    ));
+}
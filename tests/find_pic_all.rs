@@ -0,0 +1,76 @@
+use aojia::pic::{
+    Bitmap, FindPicMode, PicMatch, PicSearch, SearchDirection, Similarity, parse_pic_matches,
+};
+use aojia::{AoJia, MockBackend};
+
+fn pic_match(index: usize, name: &str, x: i32, y: i32) -> PicMatch {
+    PicMatch {
+        index,
+        name: name.to_string(),
+        x,
+        y,
+    }
+}
+
+#[test]
+fn parse_index_result() {
+    let ret = parse_pic_matches("0,10,20|1,30,40|0,5,6", "a.bmp|b.bmp").unwrap();
+    assert_eq!(
+        ret,
+        vec![
+            pic_match(0, "a.bmp", 10, 20),
+            pic_match(1, "b.bmp", 30, 40),
+            pic_match(0, "a.bmp", 5, 6),
+        ]
+    );
+}
+
+#[test]
+fn parse_name_result() {
+    let ret = parse_pic_matches("b.bmp,1,2", "a.bmp|b.bmp").unwrap();
+    assert_eq!(ret, vec![pic_match(1, "b.bmp", 1, 2)]);
+}
+
+#[test]
+fn parse_empty_result() {
+    assert_eq!(parse_pic_matches("", "a.bmp").unwrap(), vec![]);
+    assert_eq!(parse_pic_matches(" | ", "a.bmp").unwrap(), vec![]);
+}
+
+#[test]
+fn parse_invalid_result() {
+    assert!(parse_pic_matches("0,1", "a.bmp").is_err());
+    assert!(parse_pic_matches("0,1,y", "a.bmp").is_err());
+    assert!(parse_pic_matches("2,1,2", "a.bmp|b.bmp").is_err());
+    assert!(parse_pic_matches("c.bmp,1,2", "a.bmp|b.bmp").is_err());
+}
+
+#[test]
+fn find_all_with_mock() {
+    let pic = Bitmap::new(3, 2, 0xFF0000);
+    let mut screen = Bitmap::new(40, 30, 0x000000);
+    screen.paste(5, 4, &pic);
+    screen.paste(20, 4, &pic);
+    screen.paste(10, 25, &pic);
+
+    let mock = MockBackend::new();
+    mock.set_screen(screen);
+    mock.add_pic("a.bmp", pic);
+    let aojia = AoJia::with_backend(mock);
+
+    let search = PicSearch::new((0, 0, 39, 29), "a.bmp")
+        .sim(Similarity::EXACT)
+        .dir(SearchDirection::RightLeftTopDown)
+        .mode(FindPicMode::PositionOrder);
+    assert_eq!(
+        aojia.find_pic_all(&search).unwrap(),
+        vec![
+            pic_match(0, "a.bmp", 20, 4),
+            pic_match(0, "a.bmp", 5, 4),
+            pic_match(0, "a.bmp", 10, 25),
+        ]
+    );
+
+    let search = PicSearch::new((0, 0, 39, 20), "a.bmp").sim(Similarity::EXACT);
+    assert_eq!(aojia.find_pic_all(&search).unwrap().len(), 2);
+}
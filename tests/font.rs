@@ -0,0 +1,161 @@
+mod common;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::overlay::{
+    Font, FontArgs, FontStyle, FontWeight, Overlay, TextArea, TextAreaArgs, TextDirection,
+};
+use aojia::{AoJia, Error, Rect, Value};
+use common::overlay_mock;
+
+#[test]
+fn font_encoding() {
+    assert_eq!(
+        Font::default().encode().unwrap(),
+        FontArgs {
+            name: "宋体".to_string(),
+            size: 12,
+            weight: 400,
+            italic: 0,
+            underline: 0,
+            strike_out: 0,
+        }
+    );
+    let font = Font::new(" 微软雅黑 ", 20)
+        .with_weight(FontWeight::SemiBold)
+        .italic()
+        .strike_out();
+    assert_eq!(
+        font.encode().unwrap(),
+        FontArgs {
+            name: "微软雅黑".to_string(),
+            size: 20,
+            weight: 600,
+            italic: 1,
+            underline: 0,
+            strike_out: 1,
+        }
+    );
+    assert_eq!(
+        font.clone()
+            .with_style(FontStyle::UNDERLINE)
+            .encode()
+            .unwrap(),
+        FontArgs {
+            italic: 0,
+            underline: 1,
+            strike_out: 0,
+            ..font.encode().unwrap()
+        }
+    );
+    assert!(
+        font.style()
+            .contains(FontStyle::ITALIC | FontStyle::STRIKE_OUT)
+    );
+}
+
+#[test]
+fn font_validation() {
+    let invalid = |font: Font| matches!(font.encode(), Err(Error::InvalidArgument(_)));
+    assert!(invalid(Font::new("  ", 12)));
+    assert!(invalid(Font::new(&"字".repeat(32), 12)));
+    assert!(!invalid(Font::new(&"字".repeat(31), 12)));
+    assert!(invalid(Font::new("宋体", 0)));
+    assert!(invalid(Font::new("宋体", 501)));
+    assert!(!invalid(Font::new("宋体", 500)));
+}
+
+#[test]
+fn weight_round_trip() {
+    for weight in (100..=900).step_by(100) {
+        assert_eq!(i32::from(FontWeight::try_from(weight).unwrap()), weight);
+    }
+    assert!(FontWeight::try_from(450).is_err());
+    assert!(FontWeight::try_from(1000).is_err());
+    assert!(FontWeight::Bold > FontWeight::Normal);
+}
+
+#[test]
+fn text_area_encoding() {
+    let area = TextArea::new(Rect::new(5, 6, 195, 96), 8).with_direction(TextDirection::Up);
+    assert_eq!(
+        area.encode().unwrap(),
+        TextAreaArgs {
+            x1: 5,
+            y1: 6,
+            x2: 195,
+            y2: 96,
+            row: 8,
+            dir: 1,
+        }
+    );
+    assert_eq!(
+        TextArea::new(Rect::new(0, 0, 9, 9), 1)
+            .encode()
+            .unwrap()
+            .dir,
+        0
+    );
+    assert_eq!(TextDirection::try_from(1).unwrap(), TextDirection::Up);
+    assert!(TextDirection::try_from(2).is_err());
+
+    for area in [
+        TextArea::new(Rect::new(0, 0, 9, 9), 0),
+        TextArea::new(Rect::new(0, 0, 9, 9), 1001),
+        TextArea::new(Rect::new(10, 0, 9, 9), 1),
+        TextArea::new(Rect::new(-1, 0, 9, 9), 1),
+    ] {
+        assert!(matches!(area.encode(), Err(Error::InvalidArgument(_))));
+    }
+}
+
+#[test]
+fn set_style_validates_before_calling() {
+    let mock = overlay_mock(9);
+    let aojia = AoJia::with_backend(mock.clone());
+    let mut overlay = Overlay::new(&aojia, Rect::from_size(0, 0, 200, 100)).unwrap();
+    mock.clear_calls();
+
+    let font = Font::new("黑体", 14).bold();
+    assert!(
+        overlay
+            .set_style(font.clone(), TextArea::new(Rect::new(0, 0, 199, 99), 0))
+            .is_err()
+    );
+    assert!(overlay.set_font(Font::new("", 14)).is_err());
+    assert!(mock.calls().is_empty());
+    assert_eq!(overlay.font(), &Font::default());
+
+    let area = TextArea::new(Rect::new(0, 0, 199, 99), 5);
+    overlay.set_style(font.clone(), area).unwrap();
+    assert_eq!(mock.call_names(), ["SetFont", "SetTextD"]);
+    assert_eq!((overlay.font(), overlay.text_area()), (&font, &area));
+}
+
+#[test]
+fn set_style_restores_font_on_failure() {
+    let mock = overlay_mock(9);
+    let aojia = AoJia::with_backend(mock.clone());
+    let mut overlay = Overlay::new(&aojia, Rect::from_size(0, 0, 200, 100)).unwrap();
+    mock.on("SetTextD", |_| Ok(Value::I32(0)));
+    mock.clear_calls();
+
+    let err = overlay
+        .set_style(
+            Font::new("黑体", 30),
+            TextArea::new(Rect::new(0, 0, 99, 99), 2),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Failed {
+            fun: "SetTextD",
+            ..
+        }
+    ));
+    assert_eq!(mock.call_names(), ["SetFont", "SetTextD", "SetFont"]);
+    let calls = mock.calls();
+    // 恢复为默认字体
+    assert_eq!(calls[2].args[1], RecordedArg::In("宋体".into()));
+    assert_eq!(overlay.font(), &Font::default());
+    assert_eq!(overlay.text_area().rows(), 1);
+}
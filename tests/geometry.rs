@@ -0,0 +1,301 @@
+mod common;
+
+use aojia::{AoJia, CoordConversion, CoordSpace, Error, MockBackend, Point, Rect, Region, Value};
+use common::{in_i32, set_out};
+
+const HWND: i32 = 7;
+const ORIGIN: (i32, i32) = (100, 50);
+const SIZE: (i32, i32) = (800, 600);
+
+/// 句柄为 HWND 的窗口客户区原点在 ORIGIN，大小为 SIZE，其他句柄调用失败
+fn mock_window() -> MockBackend {
+    let mock = MockBackend::new();
+    mock.on("ClientToScreen", |args| {
+        if in_i32(args, 0) != HWND {
+            return Ok(Value::I32(0));
+        }
+        set_out(args, 1, ORIGIN.0);
+        set_out(args, 2, ORIGIN.1);
+        Ok(Value::I32(1))
+    });
+    mock.on("GetClientSize", |args| {
+        if in_i32(args, 0) != HWND {
+            return Ok(Value::I32(0));
+        }
+        set_out(args, 1, SIZE.0);
+        set_out(args, 2, SIZE.1);
+        Ok(Value::I32(1))
+    });
+    mock.on("ClientOrScreen", |args| {
+        if in_i32(args, 0) != HWND {
+            return Ok(Value::I32(0));
+        }
+        let (x, y) = (in_i32(args, 1), in_i32(args, 2));
+        let (x, y) = match in_i32(args, 5) {
+            0 => (x + ORIGIN.0, y + ORIGIN.1),
+            _ => (x - ORIGIN.0, y - ORIGIN.1),
+        };
+        set_out(args, 3, x);
+        set_out(args, 4, y);
+        Ok(Value::I32(1))
+    });
+    mock
+}
+
+#[test]
+fn grid_splits_evenly() {
+    let rect = Rect::new(0, 0, 99, 49);
+    assert_eq!(rect.grid(2, 1, 0, 0).unwrap(), Rect::new(0, 0, 49, 49));
+    assert_eq!(rect.grid(2, 1, 1, 0).unwrap(), Rect::new(50, 0, 99, 49));
+    assert_eq!(rect.grid(4, 2, 3, 1).unwrap(), Rect::new(75, 25, 99, 49));
+}
+
+#[test]
+fn grid_covers_uneven_sizes() {
+    let rect = Rect::new(10, 10, 19, 19);
+    let cells: Vec<Rect> = (0..3).map(|c| rect.grid(3, 1, c, 0).unwrap()).collect();
+    assert_eq!(cells[0].x1, 10);
+    assert_eq!(cells[2].x2, 19);
+    assert_eq!(cells.iter().map(Rect::width).sum::<i32>(), 10);
+    assert_eq!(cells[0].x2 + 1, cells[1].x1);
+    assert_eq!(cells[1].x2 + 1, cells[2].x1);
+}
+
+#[test]
+fn grid_rejects_cell_outside() {
+    let rect = Rect::new(0, 0, 99, 99);
+    assert!(matches!(
+        rect.grid(2, 2, 2, 0),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        rect.grid(2, 2, 0, 2),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        rect.grid(0, 0, 0, 0),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn percent_takes_sub_rect() {
+    let rect = Rect::new(100, 200, 299, 299);
+    assert_eq!(
+        rect.percent(0.0, 0.0, 1.0, 1.0).unwrap(),
+        Rect::new(100, 200, 299, 299)
+    );
+    assert_eq!(
+        rect.percent(0.5, 0.0, 1.0, 0.5).unwrap(),
+        Rect::new(200, 200, 299, 249)
+    );
+}
+
+#[test]
+fn percent_rejects_invalid_ratios() {
+    let rect = Rect::new(0, 0, 99, 99);
+    for (l, t, r, b) in [
+        (-0.1, 0.0, 1.0, 1.0),
+        (0.0, 0.0, 1.1, 1.0),
+        (0.5, 0.0, 0.5, 1.0),
+        (0.0, 0.8, 1.0, 0.2),
+        (f64::NAN, 0.0, 1.0, 1.0),
+    ] {
+        assert!(
+            matches!(rect.percent(l, t, r, b), Err(Error::InvalidArgument(_))),
+            "({}, {}, {}, {})",
+            l,
+            t,
+            r,
+            b
+        );
+    }
+}
+
+#[test]
+fn intersect_overlapping_and_disjoint() {
+    let a = Rect::new(0, 0, 9, 9);
+    assert_eq!(
+        a.intersect(&Rect::new(5, 5, 20, 20)),
+        Some(Rect::new(5, 5, 9, 9))
+    );
+    assert_eq!(
+        a.intersect(&Rect::new(9, 9, 9, 9)),
+        Some(Rect::new(9, 9, 9, 9))
+    );
+    assert_eq!(a.intersect(&Rect::new(10, 0, 20, 9)), None);
+}
+
+#[test]
+fn region_helpers_keep_space() {
+    let region = Region::client(HWND, (0, 0, 99, 99));
+    let cell = region.grid(2, 2, 1, 1).unwrap();
+    assert_eq!(cell.space, CoordSpace::Client(HWND));
+    assert_eq!(cell.rect, Rect::new(50, 50, 99, 99));
+
+    let part = Region::screen((0, 0, 99, 99))
+        .percent(0.0, 0.0, 0.5, 0.5)
+        .unwrap();
+    assert_eq!(part, Region::screen((0, 0, 49, 49)));
+}
+
+#[test]
+fn to_screen_offsets_by_client_origin() {
+    let aojia = AoJia::with_backend(mock_window());
+    let screen = aojia
+        .to_screen(&Region::client(HWND, (10, 20, 30, 40)))
+        .unwrap();
+    assert_eq!(screen, Region::screen((110, 70, 130, 90)));
+
+    let already = Region::screen((1, 2, 3, 4));
+    assert_eq!(aojia.to_screen(&already).unwrap(), already);
+}
+
+#[test]
+fn to_client_offsets_back() {
+    let aojia = AoJia::with_backend(mock_window());
+    let client = aojia
+        .to_client(&Region::screen((110, 70, 130, 90)), HWND)
+        .unwrap();
+    assert_eq!(client, Region::client(HWND, (10, 20, 30, 40)));
+}
+
+#[test]
+fn client_region_covers_client_area() {
+    let aojia = AoJia::with_backend(mock_window());
+    let region = aojia.client_region(HWND).unwrap();
+    assert_eq!(region, Region::client(HWND, (0, 0, 799, 599)));
+}
+
+#[test]
+fn clamp_to_client_keeps_original_space() {
+    let aojia = AoJia::with_backend(mock_window());
+
+    let client = aojia
+        .clamp_to_client(&Region::client(HWND, (-10, -10, 900, 100)), HWND)
+        .unwrap();
+    assert_eq!(client, Region::client(HWND, (0, 0, 799, 100)));
+
+    let screen = aojia
+        .clamp_to_client(&Region::screen((0, 0, 200, 200)), HWND)
+        .unwrap();
+    assert_eq!(screen, Region::screen((100, 50, 200, 200)));
+}
+
+#[test]
+fn clamp_to_client_rejects_region_outside() {
+    let aojia = AoJia::with_backend(mock_window());
+    let outside = Region::client(HWND, (900, 0, 950, 50));
+    assert!(matches!(
+        aojia.clamp_to_client(&outside, HWND),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn plugin_failure_is_reported_as_failed() {
+    let aojia = AoJia::with_backend(mock_window());
+    let gone = HWND + 1;
+    assert!(matches!(
+        aojia.client_origin(gone),
+        Err(Error::Failed {
+            fun: "ClientToScreen",
+            ret: 0
+        })
+    ));
+    assert!(matches!(
+        aojia.client_region(gone),
+        Err(Error::Failed {
+            fun: "GetClientSize",
+            ret: 0
+        })
+    ));
+    assert!(matches!(
+        aojia.client_to_screen(gone, Point::new(1, 1)),
+        Err(Error::Failed {
+            fun: "ClientOrScreen",
+            ret: 0
+        })
+    ));
+    assert!(matches!(
+        aojia.to_screen(&Region::client(gone, (0, 0, 9, 9))),
+        Err(Error::Failed { .. })
+    ));
+}
+
+#[test]
+fn convert_point_in_both_directions() {
+    let aojia = AoJia::with_backend(mock_window());
+    let p = Point::new(10, 20);
+    let screen = aojia.client_to_screen(HWND, p).unwrap();
+    assert_eq!(screen, Point::new(110, 70));
+    assert_eq!(aojia.screen_to_client(HWND, screen).unwrap(), p);
+    assert_eq!(
+        aojia
+            .convert_point(HWND, Point::new(0, 0), CoordConversion::ScreenToClient)
+            .unwrap(),
+        Point::new(-100, -50)
+    );
+}
+
+#[test]
+fn convert_points_matches_single_conversions() {
+    let mock = mock_window();
+    let aojia = AoJia::with_backend(mock.clone());
+    let points = [Point::new(0, 0), Point::new(5, 7), Point::new(-3, 400)];
+    for conversion in [
+        CoordConversion::ClientToScreen,
+        CoordConversion::ScreenToClient,
+    ] {
+        let single: Vec<Point> = points
+            .iter()
+            .map(|&p| aojia.convert_point(HWND, p, conversion).unwrap())
+            .collect();
+        mock.clear_calls();
+        let batch = aojia.convert_points(HWND, &points, conversion).unwrap();
+        assert_eq!(batch, single);
+        // 只转换 (0, 0) 一次，把结果作为偏移
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "ClientOrScreen");
+    }
+}
+
+#[test]
+fn convert_rects_offsets_every_corner() {
+    let mock = mock_window();
+    let aojia = AoJia::with_backend(mock.clone());
+    let rects = [Rect::new(0, 0, 9, 9), Rect::new(20, 30, 40, 50)];
+    let screen = aojia
+        .convert_rects(HWND, &rects, CoordConversion::ClientToScreen)
+        .unwrap();
+    assert_eq!(
+        screen,
+        [Rect::new(100, 50, 109, 59), Rect::new(120, 80, 140, 100)]
+    );
+    let back = aojia
+        .convert_rects(HWND, &screen, CoordConversion::ScreenToClient)
+        .unwrap();
+    assert_eq!(back, rects);
+    assert_eq!(mock.calls().len(), 2);
+}
+
+#[test]
+fn batch_conversion_of_gone_window_fails() {
+    let aojia = AoJia::with_backend(mock_window());
+    assert!(matches!(
+        aojia.convert_points(
+            HWND + 1,
+            &[Point::new(1, 1)],
+            CoordConversion::ClientToScreen
+        ),
+        Err(Error::Failed {
+            fun: "ClientOrScreen",
+            ..
+        })
+    ));
+    assert!(matches!(
+        aojia.convert_rects(HWND + 1, &[], CoordConversion::ScreenToClient),
+        Err(Error::Failed { .. })
+    ));
+}
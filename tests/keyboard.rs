@@ -0,0 +1,206 @@
+mod common;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::input::{Chord, Key, KeyEvent};
+use aojia::{AoJia, Error, MockBackend, Value};
+use common::in_i32;
+
+#[test]
+fn key_round_trip() {
+    for key in Key::ALL {
+        assert_eq!(key.name().parse::<Key>().unwrap(), *key);
+        assert_eq!(Key::try_from(key.vk()).unwrap(), *key);
+        assert_eq!(key.to_string().to_uppercase().parse::<Key>().unwrap(), *key);
+    }
+}
+
+#[test]
+fn key_codes() {
+    assert_eq!(Key::A.vk(), 0x41);
+    assert_eq!(Key::Num0.vk(), 0x30);
+    assert_eq!(Key::F5.vk(), 0x74);
+    assert_eq!(Key::F12.vk(), 0x7B);
+    assert_eq!(i32::from(Key::Ctrl), 0x11);
+    assert_eq!("Escape".parse::<Key>().unwrap(), Key::Esc);
+    assert_eq!("control".parse::<Key>().unwrap(), Key::Ctrl);
+    assert!("f13".parse::<Key>().is_err());
+    assert!(Key::try_from(0xFF).is_err());
+}
+
+#[test]
+fn parse_chord() {
+    let chord: Chord = "ctrl+shift+F5".parse().unwrap();
+    assert_eq!(chord.keys(), [Key::Ctrl, Key::Shift, Key::F5]);
+    assert_eq!(chord.to_string(), "ctrl+shift+f5");
+    assert_eq!(
+        " Alt + Tab ".parse::<Chord>().unwrap().keys(),
+        [Key::Alt, Key::Tab]
+    );
+    assert_eq!("a".parse::<Chord>().unwrap().keys(), [Key::A]);
+}
+
+#[test]
+fn numpad_and_punctuation_keys() {
+    assert_eq!(Key::Numpad0.vk(), 0x60);
+    assert_eq!(Key::Numpad9.vk(), 0x69);
+    assert_eq!(Key::Divide.vk(), 0x6F);
+    assert_eq!(Key::Semicolon.vk(), 0xBA);
+    assert_eq!(Key::Quote.vk(), 0xDE);
+    assert_eq!("NumPad1".parse::<Key>().unwrap(), Key::Numpad1);
+    assert_eq!("numpad*".parse::<Key>().unwrap(), Key::Multiply);
+    assert_eq!("minus".parse::<Key>().unwrap(), Key::Minus);
+
+    let chord: Chord = "ctrl+numpad1".parse().unwrap();
+    assert_eq!(chord.keys(), [Key::Ctrl, Key::Numpad1]);
+    let chord: Chord = "ctrl+-".parse().unwrap();
+    assert_eq!(chord.keys(), [Key::Ctrl, Key::Minus]);
+    assert_eq!(chord.to_string(), "ctrl+-");
+    assert_eq!(
+        "ctrl+add".parse::<Chord>().unwrap().keys(),
+        [Key::Ctrl, Key::Add]
+    );
+    for (s, key) in [
+        (";", Key::Semicolon),
+        ("=", Key::Equal),
+        (",", Key::Comma),
+        ("-", Key::Minus),
+        (".", Key::Period),
+        ("/", Key::Slash),
+        ("`", Key::Backquote),
+        ("[", Key::LeftBracket),
+        ("\\", Key::Backslash),
+        ("]", Key::RightBracket),
+        ("'", Key::Quote),
+    ] {
+        let chord: Chord = format!("shift+{}", s).parse().unwrap();
+        assert_eq!(chord.keys(), [Key::Shift, key], "{}", s);
+        assert_eq!(chord.to_string(), format!("shift+{}", s));
+    }
+}
+
+#[test]
+fn invalid_chords() {
+    for s in [
+        "",
+        "ctrl+",
+        "+a",
+        "ctrl++a",
+        "ctrl++",
+        "ctrl+ctrl",
+        "ctrl+foo",
+    ] {
+        assert!(
+            matches!(s.parse::<Chord>(), Err(Error::InvalidArgument(_))),
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn chord_expansion() {
+    let chord: Chord = "ctrl+shift+F5".parse().unwrap();
+    assert_eq!(
+        chord.events(),
+        [
+            KeyEvent::Down(Key::Ctrl),
+            KeyEvent::Down(Key::Shift),
+            KeyEvent::Down(Key::F5),
+            KeyEvent::Up(Key::F5),
+            KeyEvent::Up(Key::Shift),
+            KeyEvent::Up(Key::Ctrl),
+        ]
+    );
+}
+
+fn calls(mock: &MockBackend) -> Vec<(String, Vec<RecordedArg>)> {
+    mock.calls().into_iter().map(|c| (c.name, c.args)).collect()
+}
+
+fn call(name: &str, key: Key) -> (String, Vec<RecordedArg>) {
+    (name.to_string(), vec![RecordedArg::In(key.vk().into())])
+}
+
+#[test]
+fn press_chord_sends_events() {
+    let mock = MockBackend::new();
+    mock.on("KeyDown", |_| Ok(Value::I32(1)));
+    mock.on("KeyUp", |_| Ok(Value::I32(1)));
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.press_chord(&"ctrl+c".parse().unwrap()).unwrap();
+    assert_eq!(
+        calls(&mock),
+        [
+            call("KeyDown", Key::Ctrl),
+            call("KeyDown", Key::C),
+            call("KeyUp", Key::C),
+            call("KeyUp", Key::Ctrl),
+        ]
+    );
+}
+
+#[test]
+fn press_chord_releases_on_failure() {
+    let mock = MockBackend::new();
+    mock.on("KeyDown", |args| {
+        let failed = in_i32(args, 0) == Key::Alt.vk();
+        Ok(Value::I32(!failed as i32))
+    });
+    mock.on("KeyUp", |_| Ok(Value::I32(1)));
+    let aojia = AoJia::with_backend(mock.clone());
+    assert!(
+        aojia
+            .press_chord(&"ctrl+shift+alt+x".parse().unwrap())
+            .is_err()
+    );
+    assert_eq!(
+        calls(&mock),
+        [
+            call("KeyDown", Key::Ctrl),
+            call("KeyDown", Key::Shift),
+            call("KeyDown", Key::Alt),
+            call("KeyUp", Key::Shift),
+            call("KeyUp", Key::Ctrl),
+        ]
+    );
+}
+
+#[test]
+fn press_chord_releases_every_key_when_one_release_fails() {
+    let mock = MockBackend::new();
+    mock.on("KeyDown", |_| Ok(Value::I32(1)));
+    mock.on("KeyUp", |args| {
+        let failed = in_i32(args, 0) == Key::Shift.vk();
+        Ok(Value::I32(!failed as i32))
+    });
+    let aojia = AoJia::with_backend(mock.clone());
+    assert!(matches!(
+        aojia.press_chord(&"ctrl+shift+x".parse().unwrap()),
+        Err(Error::Failed { fun: "KeyUp", .. })
+    ));
+    assert_eq!(
+        calls(&mock)[3..],
+        [
+            call("KeyUp", Key::X),
+            call("KeyUp", Key::Shift),
+            call("KeyUp", Key::Ctrl),
+        ]
+    );
+}
+
+#[test]
+fn key_state_and_typing() {
+    let mock = MockBackend::new();
+    mock.on("GetKeyState", |_| Ok(Value::I32(1)));
+    mock.on("KeyPressStr", |_| Ok(Value::I32(1)));
+    let aojia = AoJia::with_backend(mock.clone());
+    assert!(aojia.key_state(Key::CapsLock).unwrap());
+    aojia.type_text("hello", 50).unwrap();
+    assert_eq!(
+        calls(&mock)[1],
+        (
+            "KeyPressStr".to_string(),
+            vec![RecordedArg::In("hello".into()), RecordedArg::In(50.into())]
+        )
+    );
+}
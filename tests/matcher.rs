@@ -0,0 +1,206 @@
+use aojia::pic::{Bitmap, FindPicMode, Matcher, PicMatch, SearchDirection, Similarity};
+
+/// 4x4 的图片，每个像素颜色不同
+fn pic() -> Bitmap {
+    Bitmap::from_pixels(4, 4, (1..=16).map(|i| i * 0x0F0F0F).collect()).unwrap()
+}
+
+fn matcher(color_p: &str, sim: f64, dir: SearchDirection) -> Matcher {
+    Matcher::new(
+        color_p,
+        Similarity::new(sim).unwrap(),
+        dir,
+        FindPicMode::PicOrder,
+    )
+    .unwrap()
+}
+
+fn at(x: i32, y: i32) -> PicMatch {
+    PicMatch {
+        index: 0,
+        name: "a".to_string(),
+        x,
+        y,
+    }
+}
+
+#[test]
+fn color_p_pixels_are_ignored() {
+    let mut pic = pic();
+    pic.set_pixel(0, 0, 0xFF00FF);
+    pic.set_pixel(3, 3, 0x00FFFF);
+    let mut screen = Bitmap::new(20, 20, 0);
+    screen.paste(5, 6, &pic);
+    screen.set_pixel(5, 6, 0x123456);
+    screen.set_pixel(8, 9, 0x654321);
+    let pics = [("a", &pic)];
+    let region = (0, 0, 19, 19);
+
+    let exact = matcher("", 1.0, SearchDirection::default());
+    assert_eq!(exact.find(&screen, region, &pics), None);
+    let one = matcher("FF00FF", 1.0, SearchDirection::default());
+    assert_eq!(one.find(&screen, region, &pics), None);
+    let both = matcher("ff00ff|00FFFF", 1.0, SearchDirection::default());
+    assert_eq!(both.find(&screen, region, &pics), Some(at(5, 6)));
+    // 只比较 14 个非透明像素
+    assert_eq!(both.score(&screen, &pic, 5, 6), 1.0);
+
+    assert!(
+        Matcher::new(
+            "FF00",
+            Similarity::EXACT,
+            SearchDirection::default(),
+            FindPicMode::PicOrder
+        )
+        .is_err()
+    );
+    // 全部透明的图片找不到
+    let blank = Bitmap::new(2, 2, 0xFF00FF);
+    assert_eq!(one.find(&screen, region, &[("a", &blank)]), None);
+    assert_eq!(one.score(&screen, &blank, 0, 0), 0.0);
+}
+
+/// 16 个像素中有 2 个不同，Sim 不超过 0.875 时才能找到
+#[test]
+fn sim_allows_missed_pixels() {
+    let pic = pic();
+    let mut screen = Bitmap::new(10, 10, 0);
+    screen.paste(3, 3, &pic);
+    screen.set_pixel(3, 3, 0xABCDEF);
+    screen.set_pixel(6, 6, 0xABCDEF);
+    let pics = [("a", &pic)];
+    let region = (0, 0, 9, 9);
+
+    let dir = SearchDirection::default();
+    assert_eq!(matcher("", 1.0, dir).find(&screen, region, &pics), None);
+    assert_eq!(matcher("", 0.9, dir).find(&screen, region, &pics), None);
+    assert_eq!(matcher("", 0.876, dir).find(&screen, region, &pics), None);
+    assert_eq!(
+        matcher("", 0.875, dir).find(&screen, region, &pics),
+        Some(at(3, 3))
+    );
+    assert_eq!(matcher("", 1.0, dir).score(&screen, &pic, 3, 3), 0.875);
+}
+
+/// 四个角各有一张图片，每种 Dir 先找到对应的角
+#[test]
+fn dir_scan_order() {
+    let pic = pic();
+    let mut screen = Bitmap::new(30, 30, 0);
+    for (x, y) in [(2, 2), (20, 2), (2, 20), (20, 20)] {
+        screen.paste(x, y, &pic);
+    }
+    let pics = [("a", &pic)];
+    let region = (0, 0, 29, 29);
+
+    for (dir, expected) in [
+        (
+            SearchDirection::LeftRightTopDown,
+            [(2, 2), (20, 2), (2, 20), (20, 20)],
+        ),
+        (
+            SearchDirection::LeftRightBottomUp,
+            [(2, 20), (20, 20), (2, 2), (20, 2)],
+        ),
+        (
+            SearchDirection::RightLeftTopDown,
+            [(20, 2), (2, 2), (20, 20), (2, 20)],
+        ),
+        (
+            SearchDirection::RightLeftBottomUp,
+            [(20, 20), (2, 20), (20, 2), (2, 2)],
+        ),
+    ] {
+        let matcher = matcher("", 1.0, dir);
+        let expected: Vec<PicMatch> = expected.iter().map(|&(x, y)| at(x, y)).collect();
+        assert_eq!(
+            matcher.find(&screen, region, &pics).as_ref(),
+            expected.first(),
+            "{:?}",
+            dir
+        );
+        assert_eq!(
+            matcher.find_all(&screen, region, &pics),
+            expected,
+            "{:?}",
+            dir
+        );
+    }
+}
+
+#[test]
+fn region_is_clipped_and_inclusive() {
+    let pic = pic();
+    let mut screen = Bitmap::new(10, 10, 0);
+    screen.paste(6, 6, &pic);
+    let pics = [("a", &pic)];
+    let matcher = matcher("", 1.0, SearchDirection::default());
+    // 右下角包含在区域内，超出屏幕的部分被裁掉
+    assert_eq!(matcher.find(&screen, (6, 6, 9, 9), &pics), Some(at(6, 6)));
+    assert_eq!(
+        matcher.find(&screen, (-5, -5, 100, 100), &pics),
+        Some(at(6, 6))
+    );
+    assert_eq!(matcher.find(&screen, (6, 6, 8, 9), &pics), None);
+    assert_eq!(matcher.find(&screen, (0, 0, 9, 9), &[]), None);
+}
+
+#[test]
+fn score_and_best() {
+    let pic = pic();
+    let mut screen = Bitmap::new(12, 12, 0);
+    screen.paste(1, 1, &pic);
+    screen.paste(7, 7, &pic);
+    for x in 7..11 {
+        screen.set_pixel(x, 7, 0xABCDEF);
+    }
+    let matcher = matcher("", 1.0, SearchDirection::default());
+
+    assert_eq!(matcher.score(&screen, &pic, 1, 1), 1.0);
+    assert_eq!(matcher.score(&screen, &pic, 7, 7), 0.75);
+    // 超出屏幕
+    assert_eq!(matcher.score(&screen, &pic, 10, 10), 0.0);
+    assert_eq!(matcher.score(&screen, &pic, -1, 0), 0.0);
+
+    assert_eq!(
+        matcher.best(&screen, (0, 0, 11, 11), &pic),
+        Some((1, 1, 1.0))
+    );
+    assert_eq!(
+        matcher.best(&screen, (5, 5, 11, 11), &pic),
+        Some((7, 7, 0.75))
+    );
+    assert_eq!(matcher.best(&screen, (0, 0, 2, 2), &pic), None);
+}
+
+#[test]
+fn best_agrees_with_exhaustive_scores() {
+    // 颜色只有三种，很多位置部分相同
+    let palette = [0x000000, 0xFFFFFF, 0xFF00FF];
+    let mut rng = aojia::Rng::new(5);
+    let mut screen = Bitmap::new(30, 20, 0);
+    for y in 0..20 {
+        for x in 0..30 {
+            screen.set_pixel(x, y, palette[rng.range(0, 2) as usize]);
+        }
+    }
+    let pic = Bitmap::from_pixels(
+        5,
+        4,
+        (0..20).map(|_| palette[rng.range(0, 2) as usize]).collect(),
+    )
+    .unwrap();
+    for color_p in ["", "FF00FF"] {
+        let matcher = matcher(color_p, 1.0, SearchDirection::default());
+        let mut expected: Option<(i32, i32, f64)> = None;
+        for y in 0..=16 {
+            for x in 0..=25 {
+                let score = matcher.score(&screen, &pic, x, y);
+                if expected.is_none_or(|b| score > b.2) {
+                    expected = Some((x, y, score));
+                }
+            }
+        }
+        assert_eq!(matcher.best(&screen, (0, 0, 29, 19), &pic), expected);
+    }
+}
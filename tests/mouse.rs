@@ -0,0 +1,101 @@
+mod common;
+
+use std::time::Duration;
+
+use aojia::input::MouseButton;
+use aojia::{AoJia, DelayPolicy, DelayScheduler, Error, MockBackend, MockClock, Point, Value};
+use common::set_out;
+
+fn mock() -> MockBackend {
+    MockBackend::succeeding(&[
+        "LeftClick",
+        "LeftDoubleClick",
+        "RightClick",
+        "RightDown",
+        "RightUp",
+        "MiddleClick",
+        "MiddleDown",
+        "MiddleUp",
+        "WheelUp",
+        "MoveR",
+    ])
+}
+
+#[test]
+fn buttons_map_to_plugin_functions() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.click(MouseButton::Right).unwrap();
+    aojia.mouse_down(MouseButton::Middle).unwrap();
+    aojia.mouse_up(MouseButton::Middle).unwrap();
+    aojia.double_click(MouseButton::Left).unwrap();
+    aojia.double_click(MouseButton::Middle).unwrap();
+    aojia.wheel_up().unwrap();
+    aojia.move_by(-5, 10).unwrap();
+    assert_eq!(
+        mock.call_names(),
+        [
+            "RightClick",
+            "MiddleDown",
+            "MiddleUp",
+            "LeftDoubleClick",
+            "MiddleClick",
+            "MiddleClick",
+            "WheelUp",
+            "MoveR",
+        ]
+    );
+}
+
+/// 双击只在最后停顿一次，两次单击之间没有间隔
+#[test]
+fn double_click_pauses_once() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let clock = MockClock::new();
+    aojia.set_delay(Some(
+        DelayScheduler::new(DelayPolicy::fixed(Duration::from_millis(80)))
+            .unwrap()
+            .clock(clock.clone()),
+    ));
+    for button in [MouseButton::Left, MouseButton::Right, MouseButton::Middle] {
+        mock.clear_calls();
+        let before = clock.elapsed();
+        aojia.double_click(button).unwrap();
+        assert_eq!(
+            clock.elapsed() - before,
+            Duration::from_millis(80),
+            "{}",
+            button
+        );
+    }
+    assert_eq!(mock.call_names(), ["MiddleClick", "MiddleClick"]);
+}
+
+#[test]
+fn failure_is_error() {
+    let mock = MockBackend::new();
+    mock.on("RightUp", |_| Ok(Value::I32(0)));
+    let aojia = AoJia::with_backend(mock);
+    assert!(matches!(
+        aojia.mouse_up(MouseButton::Right),
+        Err(Error::Failed {
+            fun: "RightUp",
+            ret: 0
+        })
+    ));
+}
+
+#[test]
+fn cursor_pos() {
+    let mock = MockBackend::new();
+    mock.on("GetCursorPos", |args| {
+        set_out(args, 0, 320);
+        set_out(args, 1, 240);
+        Ok(Value::I32(1))
+    });
+    mock.on("GetCursorShape", |_| Ok("1a2b3c".into()));
+    let aojia = AoJia::with_backend(mock);
+    assert_eq!(aojia.cursor_pos().unwrap(), Point::new(320, 240));
+    assert_eq!(aojia.cursor_shape().unwrap(), "1a2b3c");
+}
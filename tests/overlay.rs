@@ -0,0 +1,161 @@
+mod common;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::overlay::{Color, Font, Overlay, TextArea};
+use aojia::{AoJia, Error, Rect, Value};
+use common::{count_calls, overlay_mock};
+
+fn args(values: Vec<Value>) -> Vec<RecordedArg> {
+    values.into_iter().map(RecordedArg::In).collect()
+}
+
+#[test]
+fn color_hex() {
+    assert_eq!(Color::rgb(0x12, 0xAB, 0x03).to_string(), "12AB03");
+    assert_eq!(Color::RED.to_string(), "FF0000");
+    assert_eq!("00ff00".parse::<Color>().unwrap(), Color::GREEN);
+    assert!("0xFF00".parse::<Color>().is_err());
+    assert!("GGGGGG".parse::<Color>().is_err());
+}
+
+#[test]
+fn create_print_and_close_on_drop() {
+    let mock = overlay_mock(77);
+    let aojia = AoJia::with_backend(mock.clone());
+    {
+        let mut overlay = Overlay::new(&aojia, Rect::from_size(10, 20, 300, 100)).unwrap();
+        assert_eq!(overlay.hwnd(), 77);
+        overlay.set_color(Color::YELLOW, Color::BLACK);
+        overlay.print("血量 100").unwrap();
+        overlay.clear().unwrap();
+    }
+
+    let calls = mock.calls();
+    let names: Vec<&str> = calls.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "CreateWindows",
+            "SetFont",
+            "SetTextD",
+            "DrawTextD",
+            "SetTextD",
+            "SetWindowState"
+        ]
+    );
+    assert_eq!(
+        calls[0].args,
+        args(vec![
+            10.into(),
+            20.into(),
+            300.into(),
+            100.into(),
+            0.into(),
+            0.into(),
+            0.into()
+        ])
+    );
+    assert_eq!(
+        calls[2].args,
+        args(vec![
+            77.into(),
+            0.into(),
+            0.into(),
+            299.into(),
+            99.into(),
+            1.into(),
+            0.into()
+        ])
+    );
+    assert_eq!(
+        calls[3].args,
+        args(vec![
+            77.into(),
+            "血量 100".into(),
+            "FFFF00".into(),
+            "000000".into()
+        ])
+    );
+    assert_eq!(calls[5].args, args(vec![77.into(), 0.into()]));
+}
+
+#[test]
+fn font_and_text_area_are_applied() {
+    let mock = overlay_mock(77);
+    let aojia = AoJia::with_backend(mock.clone());
+    let mut overlay = Overlay::new(&aojia, Rect::new(0, 0, 200, 100)).unwrap();
+    mock.clear_calls();
+
+    let font = Font::new("微软雅黑", 16).bold().underline();
+    overlay.set_font(font.clone()).unwrap();
+    overlay
+        .set_text_area(TextArea::new(Rect::new(5, 5, 195, 95), 4))
+        .unwrap();
+    assert_eq!(overlay.font(), &font);
+    assert_eq!(overlay.text_area().rows(), 4);
+
+    let calls = mock.calls();
+    assert_eq!(
+        calls[0].args,
+        args(vec![
+            overlay.hwnd().into(),
+            "微软雅黑".into(),
+            16.into(),
+            700.into(),
+            0.into(),
+            1.into(),
+            0.into()
+        ])
+    );
+    assert_eq!(calls[1].name, "SetTextD");
+    overlay.close().unwrap();
+    assert_eq!(mock.calls().len(), 3);
+}
+
+#[test]
+fn create_failure() {
+    let mock = overlay_mock(77);
+    mock.on("CreateWindows", |_| Ok(Value::I32(0)));
+    let aojia = AoJia::with_backend(mock.clone());
+    assert!(matches!(
+        Overlay::new(&aojia, Rect::new(0, 0, 100, 100)),
+        Err(Error::Failed {
+            fun: "CreateWindows",
+            ..
+        })
+    ));
+    assert!(matches!(
+        Overlay::new(&aojia, Rect::new(10, 10, 9, 50)),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(mock.calls().iter().all(|c| c.name != "SetWindowState"));
+}
+
+#[test]
+fn into_hwnd_and_close_release_once() {
+    let mock = overlay_mock(77);
+    let aojia = AoJia::with_backend(mock.clone());
+
+    let hwnd = Overlay::new(&aojia, Rect::new(0, 0, 100, 100))
+        .unwrap()
+        .into_hwnd();
+    assert_eq!(hwnd, 77);
+    assert_eq!(count_calls(&mock, "SetWindowState"), 0);
+
+    Overlay::new(&aojia, Rect::new(0, 0, 100, 100))
+        .unwrap()
+        .close()
+        .unwrap();
+    assert_eq!(count_calls(&mock, "SetWindowState"), 1);
+
+    mock.on("SetWindowState", |_| Ok(Value::I32(0)));
+    let overlay = Overlay::new(&aojia, Rect::new(0, 0, 100, 100)).unwrap();
+    assert!(matches!(
+        overlay.close(),
+        Err(Error::Failed {
+            fun: "SetWindowState",
+            ..
+        })
+    ));
+    assert_eq!(count_calls(&mock, "SetWindowState"), 2);
+}
@@ -0,0 +1,241 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::Duration;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::binding::{KeyboardMode, MouseMode, ScreenMode};
+use aojia::overlay::{Color, PicDebug};
+use aojia::pic::{Bitmap, PicSearch, Similarity, WaitOptions};
+use aojia::{AoJia, BindMode, Error, MockBackend, MockClock, Rect, Value};
+use common::{count_calls, drawn, overlay_mock, set_out};
+
+/// 屏幕 (30, 40) 处有一张 4x4 的图片 a
+fn mock() -> MockBackend {
+    let mock = overlay_mock(100);
+    // 每个窗口的句柄不同
+    let next = Arc::new(AtomicI32::new(100));
+    mock.on("CreateWindows", move |_| {
+        Ok(Value::I32(next.fetch_add(1, Ordering::SeqCst)))
+    });
+    let mut screen = Bitmap::new(100, 80, 0x000000);
+    let pic = Bitmap::from_pixels(4, 4, (0..16).map(|i| i * 0x0F0F0F).collect()).unwrap();
+    screen.paste(30, 40, &pic);
+    mock.set_screen(screen);
+    mock.add_pic("a", pic);
+    mock
+}
+
+/// 标签的文字和颜色，不含填充色块时绘制的空格
+fn texts(mock: &MockBackend) -> Vec<(String, String)> {
+    drawn(mock).into_iter().filter(|(t, _)| t != " ").collect()
+}
+
+fn search(pic: &str) -> PicSearch {
+    PicSearch::new(Rect::new(0, 0, 99, 79), pic).sim(Similarity::EXACT)
+}
+
+#[test]
+fn bitmap_encode_round_trip() {
+    let pic = Bitmap::from_pixels(
+        3,
+        2,
+        vec![0x112233, 0x445566, 0x778899, 0xAABBCC, 0, 0xFFFFFF],
+    )
+    .unwrap();
+    assert_eq!(Bitmap::decode(&pic.encode()).unwrap(), pic);
+
+    let png = pic.encode_png();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    // IEND 块的 CRC 固定
+    assert_eq!(&png[png.len() - 8..], b"IEND\xAE\x42\x60\x82");
+    assert!(pic.save(std::env::temp_dir().join("aojia.jpg")).is_err());
+}
+
+#[test]
+fn disabled_by_default() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    assert!(aojia.find_pic(&search("a")).unwrap().is_some());
+    assert_eq!(count_calls(&mock, "CreateWindows"), 0);
+    assert!(aojia.pic_debug(|d| d.windows()).is_none());
+}
+
+#[test]
+fn annotations_fade_after_timeout() {
+    let mock = mock();
+    let clock = MockClock::new();
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.set_pic_debug(Some(
+        PicDebug::new()
+            .fade(Duration::from_secs(2))
+            .clock(clock.clone()),
+    ));
+
+    let found = aojia.find_pic(&search("a")).unwrap().unwrap();
+    // 四条边、区域说明、位置标记和位置说明
+    assert_eq!(count_calls(&mock, "CreateWindows"), 7);
+    assert_eq!(
+        texts(&mock),
+        [
+            ("a 找到 1 处".to_string(), "00FF00".to_string()),
+            ("a (30, 40)".to_string(), "00FF00".to_string()),
+        ]
+    );
+    assert_eq!((found.x, found.y), (30, 40));
+
+    clock.advance(Duration::from_secs(1));
+    mock.clear_calls();
+    assert_eq!(aojia.find_pic_all(&search("a|a")).unwrap().len(), 2);
+    assert_eq!(count_calls(&mock, "SetWindowState"), 0);
+    assert_eq!(aojia.pic_debug(|d| d.windows().len()), Some(7 + 9));
+
+    // 第一次的标注到期
+    clock.advance(Duration::from_millis(1500));
+    mock.clear_calls();
+    aojia.fade_pic_debug();
+    assert_eq!(count_calls(&mock, "SetWindowState"), 7);
+    assert_eq!(aojia.pic_debug(|d| d.windows().len()), Some(9));
+
+    // 关闭调试时关闭剩下的窗口，记录仍可取回
+    mock.clear_calls();
+    let debug = aojia.set_pic_debug(None).unwrap();
+    assert_eq!(count_calls(&mock, "SetWindowState"), 9);
+    assert!(debug.windows().is_empty());
+    assert_eq!(debug.annotations().count(), 2);
+}
+
+#[test]
+fn missing_pic_is_labelled() {
+    let mock = mock();
+    mock.add_pic("b", Bitmap::new(4, 4, 0x123456));
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.set_pic_debug(Some(PicDebug::new().colors(Color::GREEN, Color::YELLOW)));
+    assert!(aojia.find_pic(&search("b")).unwrap().is_none());
+    assert_eq!(count_calls(&mock, "CreateWindows"), 5);
+    assert_eq!(
+        texts(&mock),
+        [("b 未找到".to_string(), "FFFF00".to_string())]
+    );
+    let calls = mock.calls();
+    let first = calls.iter().find(|c| c.name == "CreateWindows").unwrap();
+    // 上边框
+    assert_eq!(
+        first.args[..4],
+        [0, 0, 100, 2].map(|v| RecordedArg::In(Value::I32(v)))
+    );
+}
+
+#[test]
+fn dump_annotations() {
+    let mock = mock();
+    mock.add_pic("b", Bitmap::new(4, 4, 0x123456));
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.set_pic_debug(Some(PicDebug::new().history(2)));
+    aojia.find_pic(&search("b")).unwrap();
+    aojia
+        .find_pic(&PicSearch::new(Rect::new(10, 10, 59, 59), "a").sim(Similarity::EXACT))
+        .unwrap();
+    aojia
+        .find_pic(&PicSearch::new(Rect::new(20, 20, 69, 69), "b").sim(Similarity::EXACT))
+        .unwrap();
+
+    let dir = std::env::temp_dir().join(format!("aojia-pic-debug-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let bmp = dir.join("search.bmp");
+    aojia.pic_debug(|d| d.dump(&bmp, None)).unwrap().unwrap();
+    let image = Bitmap::open(&bmp).unwrap();
+    // 只保留最近两次，画布刚好容纳两个区域
+    assert_eq!((image.width(), image.height()), (70, 70));
+    assert_eq!(image.pixel(10, 30), 0x00FF00);
+    assert_eq!(image.pixel(69, 69), 0xFF0000);
+    assert_eq!(image.pixel(30, 36), 0x00FF00);
+    assert_eq!(image.pixel(0, 0), 0x000000);
+    assert_eq!(
+        std::fs::read_to_string(dir.join("search.txt")).unwrap(),
+        "10,10,59,59 a 找到 1 处\n  a (30, 40)\n20,20,69,69 b 未找到\n"
+    );
+
+    let screen = Bitmap::new(100, 80, 0x808080);
+    let png = dir.join("search.png");
+    aojia
+        .pic_debug(|d| d.dump(&png, Some(&screen)))
+        .unwrap()
+        .unwrap();
+    assert!(std::fs::read(&png).unwrap().starts_with(b"\x89PNG"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn find_pic_inside_accessor() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.set_pic_debug(Some(PicDebug::new()));
+    let found = aojia
+        .pic_debug(|_| aojia.find_pic(&search("a")).unwrap())
+        .unwrap();
+    assert!(found.is_some());
+    // 取出期间的找图不做标注，设置在之后放回
+    assert_eq!(count_calls(&mock, "CreateWindows"), 0);
+    assert_eq!(aojia.pic_debug(|d| d.annotations().count()), Some(0));
+
+    // 在访问期间换成新的设置时，新设置保留
+    aojia.find_pic(&search("a")).unwrap();
+    mock.clear_calls();
+    aojia.pic_debug(|_| aojia.set_pic_debug(Some(PicDebug::new().history(1))));
+    assert_eq!(aojia.pic_debug(|d| d.history), Some(1));
+    assert_eq!(count_calls(&mock, "SetWindowState"), 7);
+}
+
+#[test]
+fn wait_annotates_only_final_result() {
+    let mock = mock();
+    mock.add_pic("b", Bitmap::new(4, 4, 0x123456));
+    let aojia = AoJia::with_backend(mock.clone());
+    aojia.set_pic_debug(Some(PicDebug::new()));
+    let options = WaitOptions::new(Duration::from_secs(1), Duration::from_millis(100))
+        .clock(MockClock::new());
+    assert!(matches!(
+        aojia.wait_for_pic(&search("b"), &options),
+        Err(Error::Timeout(_))
+    ));
+    assert!(count_calls(&mock, "FindPic") > 5);
+    assert_eq!(count_calls(&mock, "CreateWindows"), 5);
+    assert_eq!(aojia.pic_debug(|d| d.annotations().count()), Some(1));
+
+    mock.clear_calls();
+    aojia.wait_for_pic(&search("a"), &options).unwrap();
+    assert_eq!(count_calls(&mock, "CreateWindows"), 7);
+}
+
+#[test]
+fn bound_regions_are_shown_on_screen() {
+    let mock = mock();
+    mock.succeed(&["KQHouTai", "GBHouTai"]);
+    // 客户区原点在屏幕 (200, 100)
+    mock.on("ClientToScreen", |args| {
+        set_out(args, 1, 200);
+        set_out(args, 2, 100);
+        Ok(Value::I32(1))
+    });
+    let aojia = AoJia::with_backend(mock.clone());
+    let mode = BindMode::new(ScreenMode::Gdi, KeyboardMode::Windows, MouseMode::Windows);
+    let _guard = aojia.bind(9, mode).unwrap();
+    aojia.set_pic_debug(Some(PicDebug::new()));
+    aojia.find_pic(&search("a")).unwrap().unwrap();
+
+    let created: Vec<Vec<RecordedArg>> = mock
+        .calls()
+        .into_iter()
+        .filter(|c| c.name == "CreateWindows")
+        .map(|c| c.args[..4].to_vec())
+        .collect();
+    let rect = |v: [i32; 4]| v.map(|v| RecordedArg::In(Value::I32(v))).to_vec();
+    // 上边框和位置标记
+    assert_eq!(created[0], rect([200, 100, 100, 2]));
+    assert_eq!(created[5], rect([228, 138, 5, 5]));
+    // 记录的仍是客户区坐标
+    let region = aojia.pic_debug(|d| d.annotations().next().unwrap().region);
+    assert_eq!(region, Some(Rect::new(0, 0, 99, 79)));
+}
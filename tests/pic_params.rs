@@ -0,0 +1,67 @@
+use aojia::Error;
+use aojia::pic::{FindPicMode, PicSearch, SearchDirection, Similarity};
+
+#[test]
+fn search_direction_range() {
+    for dir in 0..=3 {
+        assert_eq!(i32::from(SearchDirection::try_from(dir).unwrap()), dir);
+    }
+    for dir in [-1, 4, i32::MAX] {
+        assert!(matches!(
+            SearchDirection::try_from(dir),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+    assert_eq!(
+        SearchDirection::default(),
+        SearchDirection::LeftRightTopDown
+    );
+}
+
+#[test]
+fn find_pic_mode_range() {
+    for mode in 0..=1 {
+        assert_eq!(i32::from(FindPicMode::try_from(mode).unwrap()), mode);
+    }
+    for mode in [-1, 2] {
+        assert!(matches!(
+            FindPicMode::try_from(mode),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+}
+
+#[test]
+fn similarity_range() {
+    for sim in [0.0, 0.5, 0.9, 1.0] {
+        assert_eq!(Similarity::new(sim).unwrap().value(), sim);
+        assert_eq!(f64::from(Similarity::try_from(sim).unwrap()), sim);
+    }
+    for sim in [-0.01, 1.0001, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(
+            matches!(Similarity::new(sim), Err(Error::InvalidArgument(_))),
+            "{}",
+            sim
+        );
+        assert!(Similarity::try_from(sim).is_err());
+    }
+    assert_eq!(Similarity::default().value(), 0.9);
+    assert_eq!(Similarity::EXACT.value(), 1.0);
+}
+
+#[test]
+fn search_validation() {
+    assert!(
+        PicSearch::new((0, 0, 10, 10), "a.bmp|b.bmp")
+            .validate()
+            .is_ok()
+    );
+    for search in [
+        PicSearch::new((10, 0, 9, 10), "a.bmp"),
+        PicSearch::new((0, 0, 10, 10), ""),
+        PicSearch::new((0, 0, 10, 10), "a.bmp||b.bmp"),
+        PicSearch::new((0, 0, 10, 10), "a.bmp").color_p("FFF"),
+    ] {
+        assert!(matches!(search.validate(), Err(Error::InvalidArgument(_))));
+    }
+}
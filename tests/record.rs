@@ -0,0 +1,316 @@
+mod common;
+
+use std::time::Duration;
+
+use aojia::input::{Chord, Key, MouseButton};
+use aojia::pic::{Bitmap, FindPicMode, PicSearch, SearchDirection};
+use aojia::record::{Macro, Player, Recorder, Step, StepMismatch};
+use aojia::{AoJia, CancellationToken, Error, MockBackend, MockClock, Point, Value};
+use common::count_calls;
+
+const SCRIPT: &str = "\
+# 打开背包
+move 100 200
+click left
+click right 300 400
+
+double middle
+down left
+up left
+wheel up
+wheel down
+key ctrl+shift+F5
+type 你好 world
+wait 500
+wait 200-800
+expect bag.bmp 0 0 799 599
+expect bag.bmp|box.bmp 10 20 30 40 sim=0.95 color=000000|FF00FF timeout=3000
+";
+
+#[test]
+fn parse_script() {
+    let script: Macro = SCRIPT.parse().unwrap();
+    assert_eq!(script.steps.len(), 14);
+    assert_eq!(script.steps[0], Step::Move(Point::new(100, 200)));
+    assert_eq!(
+        script.steps[2],
+        Step::Click {
+            button: MouseButton::Right,
+            at: Some(Point::new(300, 400))
+        }
+    );
+    assert_eq!(script.steps[8], Step::Key("ctrl+shift+f5".parse().unwrap()));
+    assert_eq!(script.steps[9], Step::Type("你好 world".to_string()));
+    assert_eq!(
+        script.steps[11],
+        Step::Wait {
+            min: Duration::from_millis(200),
+            max: Duration::from_millis(800)
+        }
+    );
+    let Step::Expect { search, timeout } = &script.steps[13] else {
+        panic!("{:?}", script.steps[13]);
+    };
+    assert_eq!(search.pic_name, "bag.bmp|box.bmp");
+    assert_eq!(search.sim.value(), 0.95);
+    assert_eq!(search.color_p, "000000|FF00FF");
+    assert_eq!(*timeout, Duration::from_secs(3));
+}
+
+#[test]
+fn round_trip() {
+    let script: Macro = SCRIPT.parse().unwrap();
+    let text = script.to_string();
+    assert!(!text.contains('#'));
+    assert_eq!(text.parse::<Macro>().unwrap(), script);
+    assert_eq!(text.lines().nth(8).unwrap(), "key ctrl+shift+f5");
+}
+
+#[test]
+fn type_keeps_whitespace() {
+    for text in ["  缩进", "末尾有空格 ", " ", "a\t b"] {
+        let step = Step::Type(text.to_string());
+        let line = step.to_string();
+        assert_eq!(line.parse::<Step>().unwrap(), step, "{:?}", line);
+    }
+    assert_eq!(
+        "type\tx".parse::<Step>().unwrap(),
+        Step::Type("x".to_string())
+    );
+    assert!("type".parse::<Step>().is_err());
+}
+
+#[test]
+fn expect_keeps_dir_and_mode() {
+    let search = PicSearch::new((0, 0, 99, 99), "bag.bmp")
+        .dir(SearchDirection::RightLeftBottomUp)
+        .mode(FindPicMode::try_from(1).unwrap());
+    let step = Step::Expect {
+        search,
+        timeout: Duration::ZERO,
+    };
+    let line = step.to_string();
+    assert_eq!(line, "expect bag.bmp 0 0 99 99 dir=3 mode=1");
+    assert_eq!(line.parse::<Step>().unwrap(), step);
+    assert!("expect bag.bmp 0 0 99 99 dir=4".parse::<Step>().is_err());
+}
+
+#[test]
+fn parse_errors_have_line_numbers() {
+    let cases = [
+        ("move 1", "第 1 行"),
+        ("# c\n\nclick left\nclick up", "第 4 行"),
+        ("wait 800-200", "第 1 行"),
+        ("key ctrl++a", "第 1 行"),
+        ("type ", "第 1 行"),
+        ("expect a.bmp 0 0 10", "第 1 行"),
+        ("expect a.bmp 0 0 10 10 foo=1", "第 1 行"),
+        ("jump 1 2", "第 1 行"),
+    ];
+    for (text, line) in cases {
+        match text.parse::<Macro>() {
+            Err(Error::InvalidArgument(msg)) => assert!(msg.contains(line), "{}: {}", text, msg),
+            other => panic!("{}: {:?}", text, other),
+        }
+    }
+}
+
+fn mock() -> MockBackend {
+    let mock = MockBackend::succeeding(&[
+        "MoveTo",
+        "LeftClick",
+        "RightClick",
+        "MiddleClick",
+        "LeftDown",
+        "LeftUp",
+        "WheelUp",
+        "WheelDown",
+        "KeyDown",
+        "KeyUp",
+        "KeyPressStr",
+    ]);
+    let mut bag = Bitmap::new(8, 8, 0xFFFFFF);
+    bag.set_pixel(0, 0, 0xFF0000);
+    let mut screen = Bitmap::new(100, 100, 0);
+    screen.paste(40, 40, &bag);
+    mock.set_screen(screen);
+    mock.add_pic("bag.bmp", bag);
+    mock.add_pic("box.bmp", Bitmap::new(8, 8, 0x00FF00));
+    mock
+}
+
+#[test]
+fn play_against_mock() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let script: Macro = "\
+move 10 20
+click right 30 40
+key ctrl+a
+type abc
+wait 100
+expect bag.bmp 0 0 99 99"
+        .parse()
+        .unwrap();
+    let clock = MockClock::new();
+    let report = Player::new()
+        .clock(clock.clone())
+        .play(&aojia, &script)
+        .unwrap();
+    assert_eq!(report.iterations, 1);
+    assert_eq!(report.steps, 6);
+    assert!(report.mismatches.is_empty());
+    assert_eq!(
+        mock.call_names(),
+        [
+            "MoveTo",
+            "MoveTo",
+            "RightClick",
+            "KeyDown",
+            "KeyDown",
+            "KeyUp",
+            "KeyUp",
+            "KeyPressStr",
+            "FindPic"
+        ]
+    );
+    assert_eq!(clock.elapsed(), Duration::from_millis(100));
+}
+
+#[test]
+fn speed_and_repeat() {
+    let aojia = AoJia::with_backend(mock());
+    let script: Macro = "wait 300\nwait 100-200".parse().unwrap();
+    let clock = MockClock::new();
+    let report = Player::new()
+        .speed(2.0)
+        .repeat(3)
+        .seed(1)
+        .clock(clock.clone())
+        .play(&aojia, &script)
+        .unwrap();
+    assert_eq!(report.iterations, 3);
+    assert_eq!(report.steps, 6);
+    let elapsed = clock.elapsed();
+    assert!(
+        elapsed >= Duration::from_millis(600) && elapsed <= Duration::from_millis(750),
+        "{:?}",
+        elapsed
+    );
+}
+
+#[test]
+fn abort_on_mismatch() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let script: Macro = "expect box.bmp 0 0 99 99\nclick left".parse().unwrap();
+    match Player::new().play(&aojia, &script) {
+        Err(Error::Mismatch(msg)) => assert!(msg.contains("第 1 步"), "{}", msg),
+        other => panic!("{:?}", other),
+    }
+    assert!(!mock.call_names().contains(&"LeftClick".to_string()));
+
+    let report = Player::new()
+        .repeat(2)
+        .abort_on_mismatch(false)
+        .play(&aojia, &script)
+        .unwrap();
+    assert_eq!(
+        report.mismatches,
+        [
+            StepMismatch {
+                iteration: 0,
+                step: 0
+            },
+            StepMismatch {
+                iteration: 1,
+                step: 0
+            }
+        ]
+    );
+    assert_eq!(count_calls(&mock, "LeftClick"), 2);
+}
+
+#[test]
+fn expect_waits_until_timeout() {
+    let aojia = AoJia::with_backend(mock());
+    let script: Macro = "expect box.bmp 0 0 99 99 timeout=1000".parse().unwrap();
+    let clock = MockClock::new();
+    let ret = Player::new().clock(clock.clone()).play(&aojia, &script);
+    assert!(matches!(ret, Err(Error::Mismatch(_))));
+    assert_eq!(clock.elapsed(), Duration::from_secs(1));
+}
+
+#[test]
+fn forever_until_cancelled() {
+    let mock = mock();
+    let token = CancellationToken::new();
+    let t = token.clone();
+    let mut clicks = 0;
+    mock.on("LeftClick", move |_| {
+        clicks += 1;
+        if clicks == 5 {
+            t.cancel();
+        }
+        Ok(Value::I32(1))
+    });
+    let aojia = AoJia::with_backend(mock.clone());
+    let script: Macro = "click left".parse().unwrap();
+    let ret = Player::new().forever().cancel(token).play(&aojia, &script);
+    assert!(matches!(ret, Err(Error::Cancelled)));
+    assert_eq!(mock.call_names().len(), 5);
+}
+
+#[test]
+fn record_then_replay() {
+    let mock = mock();
+    let aojia = AoJia::with_backend(mock.clone());
+    let clock = MockClock::new();
+    let mut recorder = Recorder::with_clock(&aojia, clock.clone());
+    recorder.step(Step::Move(Point::new(5, 6))).unwrap();
+    clock.advance(Duration::from_millis(20));
+    recorder
+        .step(Step::Click {
+            button: MouseButton::Left,
+            at: None,
+        })
+        .unwrap();
+    clock.advance(Duration::from_millis(750));
+    recorder.line("key alt+tab").unwrap();
+    assert!(matches!(
+        recorder.line("expect box.bmp 0 0 99 99"),
+        Err(Error::Mismatch(_))
+    ));
+    recorder.line("expect bag.bmp 0 0 99 99").unwrap();
+    assert!(matches!(
+        recorder.step(Step::Type("两\n行".to_string())),
+        Err(Error::InvalidArgument(_))
+    ));
+    let script = recorder.finish();
+    assert_eq!(
+        script.to_string(),
+        "move 5 6\nclick left\nwait 750\nkey alt+tab\nexpect bag.bmp 0 0 99 99\n"
+    );
+    assert_eq!(
+        script.steps[3],
+        Step::Key(Chord::new(vec![Key::Alt, Key::Tab]).unwrap())
+    );
+
+    mock.clear_calls();
+    let clock = MockClock::new();
+    Player::new()
+        .clock(clock.clone())
+        .play(&aojia, &script)
+        .unwrap();
+    assert_eq!(clock.elapsed(), Duration::from_millis(750));
+    assert_eq!(mock.call_names()[..2], ["MoveTo", "LeftClick"]);
+}
+
+#[test]
+fn save_and_load() {
+    let script: Macro = SCRIPT.parse().unwrap();
+    let path = std::env::temp_dir().join(format!("aojia-macro-{}.txt", std::process::id()));
+    script.save(&path).unwrap();
+    assert_eq!(Macro::load(&path).unwrap(), script);
+    std::fs::remove_file(path).unwrap();
+}
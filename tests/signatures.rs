@@ -0,0 +1,604 @@
+//! 对照插件文档中的函数签名，检查每个封装函数传给插件的参数顺序、类型和按引用传递的位置，
+//! 以及返回参数是否读回到了正确的变量。
+
+use aojia::backend::mock::RecordedArg;
+use aojia::{AoJia, Arg, MockBackend, Result, Value};
+
+#[derive(Debug, Clone, Copy)]
+enum Param {
+    I32(&'static str),
+    F64(&'static str),
+    Str(&'static str),
+    /// 按引用传递的返回参数
+    OutI32(&'static str),
+    OutStr(&'static str),
+    /// 按引用传递，同时作为输入和返回
+    InOutI32(&'static str),
+}
+
+use Param::*;
+
+impl Param {
+    fn name(self) -> &'static str {
+        match self {
+            I32(name) | F64(name) | Str(name) => name,
+            OutI32(name) | OutStr(name) | InOutI32(name) => name,
+        }
+    }
+}
+
+/// 第 `i` 个参数传入的值
+fn input(param: Param, i: usize) -> Value {
+    match param {
+        I32(_) | InOutI32(_) => Value::I32(100 + i as i32),
+        F64(_) => Value::F64(i as f64 + 0.5),
+        Str(_) => Value::Str(format!("arg{}", i)),
+        OutI32(_) | OutStr(_) => Value::Empty,
+    }
+}
+
+/// 第 `i` 个参数由插件写回的值，没有返回时为 None
+fn output(param: Param, i: usize) -> Option<Value> {
+    match param {
+        OutI32(_) | InOutI32(_) => Some(Value::I32(1000 + i as i32)),
+        OutStr(_) => Some(Value::Str(format!("out{}", i))),
+        _ => None,
+    }
+}
+
+/// 按签名生成的参数值，封装函数的第 `i` 个参数使用 `i(i)`、`f(i)` 或 `s(i)`
+struct Inputs;
+
+impl Inputs {
+    fn i(&self, i: usize) -> i32 {
+        100 + i as i32
+    }
+    fn f(&self, i: usize) -> f64 {
+        i as f64 + 0.5
+    }
+    fn s(&self, i: usize) -> String {
+        format!("arg{}", i)
+    }
+}
+
+type Call = fn(&AoJia, &Inputs) -> Result<Vec<Value>>;
+
+struct Case {
+    name: &'static str,
+    params: &'static [Param],
+    /// 调用封装函数，按顺序返回读回的返回参数
+    call: Call,
+}
+
+fn cases() -> Vec<Case> {
+    vec![
+        Case {
+            name: "VerS",
+            params: &[],
+            call: |aj, _| aj.VerS().map(|_| vec![]),
+        },
+        Case {
+            name: "SetPath",
+            params: &[Str("Path")],
+            call: |aj, a| aj.SetPath(&a.s(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "GetPath",
+            params: &[],
+            call: |aj, _| aj.GetPath().map(|_| vec![]),
+        },
+        Case {
+            name: "SetErrorMsg",
+            params: &[I32("Msg")],
+            call: |aj, a| aj.SetErrorMsg(a.i(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "SetThread",
+            params: &[I32("TN")],
+            call: |aj, a| aj.SetThread(a.i(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "GetModulePath",
+            params: &[I32("PID"), I32("Hwnd"), Str("MN"), I32("Type")],
+            call: |aj, a| {
+                aj.GetModulePath(a.i(0), a.i(1), &a.s(2), a.i(3))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "GetMachineCode",
+            params: &[],
+            call: |aj, _| aj.GetMachineCode().map(|_| vec![]),
+        },
+        Case {
+            name: "GetOs",
+            params: &[
+                OutStr("SV"),
+                OutStr("SVN"),
+                OutI32("LVBN"),
+                OutStr("SDir"),
+                I32("Type"),
+            ],
+            call: |aj, a| {
+                let (mut sv, mut svn, mut lvbn, mut sdir) = Default::default();
+                aj.GetOs(&mut sv, &mut svn, &mut lvbn, &mut sdir, a.i(4))?;
+                Ok(vec![sv.into(), svn.into(), lvbn.into(), sdir.into()])
+            },
+        },
+        Case {
+            name: "FindWindow",
+            params: &[
+                I32("Parent"),
+                Str("ProName"),
+                I32("ProId"),
+                Str("Class"),
+                Str("Title"),
+                I32("Type"),
+                I32("T"),
+            ],
+            call: |aj, a| {
+                aj.FindWindow(a.i(0), &a.s(1), a.i(2), &a.s(3), &a.s(4), a.i(5), a.i(6))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "EnumWindow",
+            params: &[
+                I32("Parent"),
+                Str("ProName"),
+                I32("ProId"),
+                Str("Class"),
+                Str("Title"),
+                I32("Type"),
+                I32("T"),
+            ],
+            call: |aj, a| {
+                aj.EnumWindow(a.i(0), &a.s(1), a.i(2), &a.s(3), &a.s(4), a.i(5), a.i(6))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "GetWindowTitle",
+            params: &[I32("Hwnd")],
+            call: |aj, a| aj.GetWindowTitle(a.i(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "GetWindowClass",
+            params: &[I32("Hwnd")],
+            call: |aj, a| aj.GetWindowClass(a.i(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "GetWindowProcessId",
+            params: &[I32("Hwnd")],
+            call: |aj, a| aj.GetWindowProcessId(a.i(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "GetWindow",
+            params: &[I32("Hwnd"), I32("Flag")],
+            call: |aj, a| aj.GetWindow(a.i(0), a.i(1)).map(|_| vec![]),
+        },
+        Case {
+            name: "SetWindowSize",
+            params: &[I32("Hwnd"), I32("Width"), I32("Height")],
+            call: |aj, a| aj.SetWindowSize(a.i(0), a.i(1), a.i(2)).map(|_| vec![]),
+        },
+        Case {
+            name: "MoveWindow",
+            params: &[I32("Hwnd"), I32("x"), I32("y")],
+            call: |aj, a| aj.MoveWindow(a.i(0), a.i(1), a.i(2)).map(|_| vec![]),
+        },
+        Case {
+            name: "SetWindowState",
+            params: &[I32("Hwnd"), I32("Flag")],
+            call: |aj, a| aj.SetWindowState(a.i(0), a.i(1)).map(|_| vec![]),
+        },
+        Case {
+            name: "SetWindowTitle",
+            params: &[I32("Hwnd"), Str("Title")],
+            call: |aj, a| aj.SetWindowTitle(a.i(0), &a.s(1)).map(|_| vec![]),
+        },
+        Case {
+            name: "CreateWindows",
+            params: &[
+                I32("x"),
+                I32("y"),
+                I32("Width"),
+                I32("Height"),
+                I32("EWidth"),
+                I32("EHeight"),
+                I32("Type"),
+            ],
+            call: |aj, a| {
+                aj.CreateWindows(a.i(0), a.i(1), a.i(2), a.i(3), a.i(4), a.i(5), a.i(6))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "GetRemoteProcAddress",
+            params: &[I32("PID"), I32("Hwnd"), Str("MN"), Str("Func")],
+            call: |aj, a| {
+                aj.GetRemoteProcAddress(a.i(0), a.i(1), &a.s(2), &a.s(3))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "KQHouTai",
+            params: &[
+                I32("Hwnd"),
+                Str("Screen"),
+                Str("Keyboard"),
+                Str("Mouse"),
+                Str("Flag"),
+                I32("Type"),
+            ],
+            call: |aj, a| {
+                aj.KQHouTai(a.i(0), &a.s(1), &a.s(2), &a.s(3), &a.s(4), a.i(5))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "GBHouTai",
+            params: &[],
+            call: |aj, _| aj.GBHouTai().map(|_| vec![]),
+        },
+        Case {
+            name: "GetCPU",
+            params: &[OutStr("Type"), OutStr("CPUID")],
+            call: |aj, _| {
+                let (mut ty, mut id) = Default::default();
+                aj.GetCPU(&mut ty, &mut id)?;
+                Ok(vec![ty.into(), id.into()])
+            },
+        },
+        Case {
+            name: "GetClientSize",
+            params: &[I32("Hwnd"), OutI32("Width"), OutI32("Height")],
+            call: |aj, a| {
+                let (mut w, mut h) = Default::default();
+                aj.GetClientSize(a.i(0), &mut w, &mut h)?;
+                Ok(vec![w.into(), h.into()])
+            },
+        },
+        Case {
+            name: "GetWindowSize",
+            params: &[I32("Hwnd"), OutI32("Width"), OutI32("Height")],
+            call: |aj, a| {
+                let (mut w, mut h) = Default::default();
+                aj.GetWindowSize(a.i(0), &mut w, &mut h)?;
+                Ok(vec![w.into(), h.into()])
+            },
+        },
+        Case {
+            name: "FindPic",
+            params: &[
+                I32("x1"),
+                I32("y1"),
+                I32("x2"),
+                I32("y2"),
+                Str("PicName"),
+                Str("ColorP"),
+                F64("Sim"),
+                I32("Dir"),
+                I32("Type"),
+                OutStr("Pic"),
+                OutI32("x"),
+                OutI32("y"),
+            ],
+            call: |aj, a| {
+                let (mut pic, mut x, mut y) = Default::default();
+                aj.FindPic(
+                    a.i(0),
+                    a.i(1),
+                    a.i(2),
+                    a.i(3),
+                    &a.s(4),
+                    &a.s(5),
+                    a.f(6),
+                    a.i(7),
+                    a.i(8),
+                    &mut pic,
+                    &mut x,
+                    &mut y,
+                )?;
+                Ok(vec![pic.into(), x.into(), y.into()])
+            },
+        },
+        Case {
+            name: "FindPicEx",
+            params: &[
+                I32("x1"),
+                I32("y1"),
+                I32("x2"),
+                I32("y2"),
+                Str("PicName"),
+                Str("ColorP"),
+                F64("Sim"),
+                I32("Dir"),
+                I32("Type"),
+            ],
+            call: |aj, a| {
+                aj.FindPicEx(
+                    a.i(0),
+                    a.i(1),
+                    a.i(2),
+                    a.i(3),
+                    &a.s(4),
+                    &a.s(5),
+                    a.f(6),
+                    a.i(7),
+                    a.i(8),
+                )
+                .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "ClientToScreen",
+            params: &[I32("Hwnd"), InOutI32("x"), InOutI32("y")],
+            call: |aj, a| {
+                let (mut x, mut y) = (a.i(1), a.i(2));
+                aj.ClientToScreen(a.i(0), &mut x, &mut y)?;
+                Ok(vec![x.into(), y.into()])
+            },
+        },
+        Case {
+            name: "ClientOrScreen",
+            params: &[
+                I32("Hwnd"),
+                I32("xz"),
+                I32("yz"),
+                OutI32("x"),
+                OutI32("y"),
+                I32("Type"),
+            ],
+            call: |aj, a| {
+                let (mut x, mut y) = Default::default();
+                aj.ClientOrScreen(a.i(0), a.i(1), a.i(2), &mut x, &mut y, a.i(5))?;
+                Ok(vec![x.into(), y.into()])
+            },
+        },
+        Case {
+            name: "CompressFile",
+            params: &[Str("SF"), Str("DF"), I32("Type"), I32("Level")],
+            call: |aj, a| {
+                aj.CompressFile(&a.s(0), &a.s(1), a.i(2), a.i(3))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "UnCompressFile",
+            params: &[Str("SF"), Str("DF"), I32("Type")],
+            call: |aj, a| aj.UnCompressFile(&a.s(0), &a.s(1), a.i(2)).map(|_| vec![]),
+        },
+        Case {
+            name: "SetFont",
+            params: &[
+                I32("Hwnd"),
+                Str("Name"),
+                I32("Size"),
+                I32("Weight"),
+                I32("Italic"),
+                I32("Underline"),
+                I32("StrikeOut"),
+            ],
+            call: |aj, a| {
+                aj.SetFont(a.i(0), &a.s(1), a.i(2), a.i(3), a.i(4), a.i(5), a.i(6))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "SetTextD",
+            params: &[
+                I32("Hwnd"),
+                I32("x1"),
+                I32("y1"),
+                I32("x2"),
+                I32("y2"),
+                I32("Row"),
+                I32("Dir"),
+            ],
+            call: |aj, a| {
+                aj.SetTextD(a.i(0), a.i(1), a.i(2), a.i(3), a.i(4), a.i(5), a.i(6))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "DrawTextD",
+            params: &[I32("Hwnd"), Str("Text"), Str("Color"), Str("BkColor")],
+            call: |aj, a| {
+                aj.DrawTextD(a.i(0), &a.s(1), &a.s(2), &a.s(3))
+                    .map(|_| vec![])
+            },
+        },
+        Case {
+            name: "LeftClick",
+            params: &[],
+            call: |aj, _| aj.LeftClick().map(|_| vec![]),
+        },
+        Case {
+            name: "LeftDown",
+            params: &[],
+            call: |aj, _| aj.LeftDown().map(|_| vec![]),
+        },
+        Case {
+            name: "LeftUp",
+            params: &[],
+            call: |aj, _| aj.LeftUp().map(|_| vec![]),
+        },
+        Case {
+            name: "MoveTo",
+            params: &[I32("x"), I32("y")],
+            call: |aj, a| aj.MoveTo(a.i(0), a.i(1)).map(|_| vec![]),
+        },
+        Case {
+            name: "WheelDown",
+            params: &[],
+            call: |aj, _| aj.WheelDown().map(|_| vec![]),
+        },
+        Case {
+            name: "RightClick",
+            params: &[],
+            call: |aj, _| aj.RightClick().map(|_| vec![]),
+        },
+        Case {
+            name: "RightDown",
+            params: &[],
+            call: |aj, _| aj.RightDown().map(|_| vec![]),
+        },
+        Case {
+            name: "RightUp",
+            params: &[],
+            call: |aj, _| aj.RightUp().map(|_| vec![]),
+        },
+        Case {
+            name: "MiddleClick",
+            params: &[],
+            call: |aj, _| aj.MiddleClick().map(|_| vec![]),
+        },
+        Case {
+            name: "MiddleDown",
+            params: &[],
+            call: |aj, _| aj.MiddleDown().map(|_| vec![]),
+        },
+        Case {
+            name: "MiddleUp",
+            params: &[],
+            call: |aj, _| aj.MiddleUp().map(|_| vec![]),
+        },
+        Case {
+            name: "LeftDoubleClick",
+            params: &[],
+            call: |aj, _| aj.LeftDoubleClick().map(|_| vec![]),
+        },
+        Case {
+            name: "WheelUp",
+            params: &[],
+            call: |aj, _| aj.WheelUp().map(|_| vec![]),
+        },
+        Case {
+            name: "MoveR",
+            params: &[I32("rx"), I32("ry")],
+            call: |aj, a| aj.MoveR(a.i(0), a.i(1)).map(|_| vec![]),
+        },
+        Case {
+            name: "GetCursorPos",
+            params: &[OutI32("x"), OutI32("y")],
+            call: |aj, _| {
+                let (mut x, mut y) = (0, 0);
+                aj.GetCursorPos(&mut x, &mut y)?;
+                Ok(vec![x.into(), y.into()])
+            },
+        },
+        Case {
+            name: "GetCursorShape",
+            params: &[],
+            call: |aj, _| aj.GetCursorShape().map(|_| vec![]),
+        },
+        Case {
+            name: "KeyPress",
+            params: &[I32("VK")],
+            call: |aj, a| aj.KeyPress(a.i(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "KeyDown",
+            params: &[I32("VK")],
+            call: |aj, a| aj.KeyDown(a.i(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "KeyUp",
+            params: &[I32("VK")],
+            call: |aj, a| aj.KeyUp(a.i(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "KeyPressStr",
+            params: &[Str("Str"), I32("Delay")],
+            call: |aj, a| aj.KeyPressStr(&a.s(0), a.i(1)).map(|_| vec![]),
+        },
+        Case {
+            name: "GetKeyState",
+            params: &[I32("VK")],
+            call: |aj, a| aj.GetKeyState(a.i(0)).map(|_| vec![]),
+        },
+        Case {
+            name: "YanShi",
+            params: &[I32("RMin"), I32("RMax")],
+            call: |aj, a| aj.YanShi(a.i(0), a.i(1)).map(|_| vec![]),
+        },
+    ]
+}
+
+fn check(case: &Case) -> std::result::Result<(), String> {
+    let mock = MockBackend::new();
+    let params = case.params;
+    mock.on(case.name, move |args: &mut [Arg<'_>]| {
+        for (i, (arg, param)) in args.iter_mut().zip(params).enumerate() {
+            if let (Arg::Out(value), Some(out)) = (arg, output(*param, i)) {
+                **value = out;
+            }
+        }
+        Ok(Value::I32(1))
+    });
+    let aojia = AoJia::with_backend(mock.clone());
+    let outputs = (case.call)(&aojia, &Inputs).map_err(|e| e.to_string())?;
+
+    let calls = mock.calls();
+    let [call] = &calls[..] else {
+        return Err(format!("调用了 {} 次插件函数", calls.len()));
+    };
+    if call.name != case.name {
+        return Err(format!("调用的是 {}", call.name));
+    }
+    if call.args.len() != params.len() {
+        return Err(format!(
+            "传了 {} 个参数，应为 {}",
+            call.args.len(),
+            params.len()
+        ));
+    }
+    for (i, (arg, param)) in call.args.iter().zip(params).enumerate() {
+        let expected = match param {
+            I32(_) | F64(_) | Str(_) => RecordedArg::In(input(*param, i)),
+            OutI32(_) | OutStr(_) | InOutI32(_) => RecordedArg::Out(input(*param, i)),
+        };
+        if *arg != expected {
+            return Err(format!(
+                "第 {} 个参数 {} 传入了 {:?}，应为 {:?}",
+                i + 1,
+                param.name(),
+                arg,
+                expected
+            ));
+        }
+    }
+
+    let expected: Vec<Value> = params
+        .iter()
+        .enumerate()
+        .filter_map(|(i, param)| output(*param, i))
+        .collect();
+    if outputs != expected {
+        return Err(format!("返回参数为 {:?}，应为 {:?}", outputs, expected));
+    }
+    Ok(())
+}
+
+#[test]
+fn wrappers_match_signatures() {
+    let failures: Vec<String> = cases()
+        .iter()
+        .filter_map(|case| check(case).err().map(|e| format!("{}: {}", case.name, e)))
+        .collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn every_wrapper_has_a_signature() {
+    let names: Vec<&str> = cases().iter().map(|case| case.name).collect();
+    let missing: Vec<&str> = include_str!("../src/lib.rs")
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("pub fn "))
+        .map(|rest| &rest[..rest.find(['(', '<']).unwrap_or(rest.len())])
+        .filter(|name| name.starts_with(|c: char| c.is_ascii_uppercase()))
+        .filter(|name| !names.contains(name))
+        .collect();
+    assert!(missing.is_empty(), "缺少签名: {:?}", missing);
+}
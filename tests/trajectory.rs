@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::input::{PathStep, Trajectory};
+use aojia::{AoJia, MockBackend, MockClock, Point, Rng, Value};
+
+fn path(seed: u64, from: Point, to: Point) -> Vec<PathStep> {
+    Trajectory::default().path(from, to, &mut Rng::new(seed))
+}
+
+#[test]
+fn rng_is_deterministic() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    for _ in 0..100 {
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+    let mut rng = Rng::new(7);
+    for _ in 0..1000 {
+        let f = rng.next_f64();
+        assert!((0.0..1.0).contains(&f));
+        assert!((-3..=3).contains(&rng.range(-3, 3)));
+    }
+}
+
+#[test]
+fn same_seed_same_path() {
+    let (from, to) = (Point::new(10, 20), Point::new(600, 400));
+    assert_eq!(path(1, from, to), path(1, from, to));
+    assert_ne!(path(1, from, to), path(2, from, to));
+}
+
+#[test]
+fn path_ends_at_target() {
+    for seed in 0..50 {
+        let (from, to) = (Point::new(100, 100), Point::new(300 + seed as i32, 50));
+        let path = path(seed, from, to);
+        assert_eq!(path.last().unwrap().point, to);
+        assert_ne!(path[0].point, from);
+        assert!(path.windows(2).all(|w| w[0].point != w[1].point));
+    }
+}
+
+#[test]
+fn path_stays_near_line() {
+    let trajectory = Trajectory::default();
+    let (from, to) = (Point::new(0, 0), Point::new(500, 0));
+    for seed in 0..50 {
+        let path = trajectory.path(from, to, &mut Rng::new(seed));
+        // 贝塞尔曲线在控制点的凸包内，再留出抖动的余量
+        let limit = trajectory.curvature * 500.0 + 6.0 * trajectory.jitter;
+        for step in &path {
+            assert!((step.point.y as f64).abs() <= limit, "{:?}", step);
+            assert!((-10..=510).contains(&step.point.x), "{:?}", step);
+        }
+    }
+}
+
+#[test]
+fn step_count_and_duration() {
+    let trajectory = Trajectory {
+        jitter: 0.0,
+        curvature: 0.0,
+        step_px: 10.0,
+        min_steps: 5,
+        max_steps: 20,
+        speed: 1000.0,
+        ..Default::default()
+    };
+    let mut rng = Rng::new(3);
+    let long = trajectory.path(Point::new(0, 0), Point::new(1000, 0), &mut rng);
+    assert_eq!(long.len(), 20);
+    let total: Duration = long.iter().map(|s| s.delay).sum();
+    assert!((total.as_secs_f64() - 1.0).abs() < 0.001, "{:?}", total);
+
+    let short = trajectory.path(Point::new(0, 0), Point::new(3, 0), &mut rng);
+    assert!(short.len() <= 3);
+    assert_eq!(short.last().unwrap().point, Point::new(3, 0));
+    assert!(
+        trajectory
+            .path(Point::new(5, 5), Point::new(5, 5), &mut rng)
+            .is_empty()
+    );
+}
+
+/// 等时间间隔采样先加速后减速的曲线，中间的步长比两端大
+#[test]
+fn speed_profile_accelerates_then_decelerates() {
+    let trajectory = Trajectory {
+        jitter: 0.0,
+        curvature: 0.0,
+        ..Default::default()
+    };
+    let path = trajectory.path(Point::new(0, 0), Point::new(1000, 0), &mut Rng::new(0));
+    let xs: Vec<i32> = path.iter().map(|s| s.point.x).collect();
+    let steps: Vec<i32> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+    let mid = steps[steps.len() / 2];
+    assert!(mid > steps[0] * 3, "{:?}", steps);
+    assert!(mid > steps[steps.len() - 1] * 3, "{:?}", steps);
+}
+
+#[test]
+fn human_move_plays_path() {
+    let mock = MockBackend::new();
+    mock.on("MoveTo", |_| Ok(Value::I32(1)));
+    let aojia = AoJia::with_backend(mock.clone());
+    let clock = MockClock::new();
+    let trajectory = Trajectory::default().clock(clock.clone());
+    let (from, to) = (Point::new(0, 0), Point::new(400, 300));
+    aojia
+        .human_move(from, to, &trajectory, &mut Rng::new(9))
+        .unwrap();
+
+    let expected = trajectory.path(from, to, &mut Rng::new(9));
+    let moves: Vec<Vec<RecordedArg>> = mock.calls().into_iter().map(|c| c.args).collect();
+    let points: Vec<Vec<RecordedArg>> = expected
+        .iter()
+        .map(|s| {
+            vec![
+                RecordedArg::In(s.point.x.into()),
+                RecordedArg::In(s.point.y.into()),
+            ]
+        })
+        .collect();
+    assert_eq!(moves, points);
+    assert_eq!(
+        clock.elapsed(),
+        expected.iter().map(|s| s.delay).sum::<Duration>()
+    );
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::Duration;
+
+use aojia::pic::{Bitmap, PicSearch, WaitOptions};
+use aojia::{AoJia, Arg, CancellationToken, Error, MockBackend, MockClock, Value};
+use common::set_out;
+
+/// 第 `from` 次调用起 FindPic 找到图片，返回调用次数
+fn appear_from(mock: &MockBackend, from: i32) -> Arc<AtomicI32> {
+    let count = Arc::new(AtomicI32::new(0));
+    let calls = count.clone();
+    mock.on("FindPic", move |args: &mut [Arg<'_>]| {
+        let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let found = n >= from;
+        let ret = [
+            (9, Value::from(if found { "a.bmp" } else { "" })),
+            (10, Value::from(if found { 7 } else { -1 })),
+            (11, Value::from(if found { 8 } else { -1 })),
+        ];
+        for (i, value) in ret {
+            set_out(args, i, value);
+        }
+        Ok(Value::from(if found { 0 } else { -1 }))
+    });
+    count
+}
+
+fn search() -> PicSearch {
+    PicSearch::new((0, 0, 100, 100), "a.bmp")
+}
+
+#[test]
+fn wait_for_pic_polls_until_found() {
+    let mock = MockBackend::new();
+    let count = appear_from(&mock, 4);
+    let clock = MockClock::new();
+    let options =
+        WaitOptions::new(Duration::from_secs(5), Duration::from_millis(100)).clock(clock.clone());
+
+    let found = AoJia::with_backend(mock)
+        .wait_for_pic(&search(), &options)
+        .unwrap();
+    assert_eq!((found.x, found.y), (7, 8));
+    assert_eq!(count.load(Ordering::SeqCst), 4);
+    assert_eq!(clock.elapsed(), Duration::from_millis(300));
+}
+
+#[test]
+fn wait_for_pic_times_out() {
+    let mock = MockBackend::new();
+    let count = appear_from(&mock, i32::MAX);
+    let clock = MockClock::new();
+    let options = WaitOptions::new(Duration::from_millis(250), Duration::from_millis(100))
+        .clock(clock.clone());
+
+    let err = AoJia::with_backend(mock).wait_for_pic(&search(), &options);
+    assert!(matches!(err, Err(Error::Timeout(_))));
+    assert_eq!(count.load(Ordering::SeqCst), 4);
+    assert_eq!(clock.elapsed(), Duration::from_millis(250));
+}
+
+#[test]
+fn wait_until_gone_with_screen() {
+    let mock = MockBackend::new();
+    let pic = Bitmap::new(2, 2, 0xFFFFFF);
+    let mut screen = Bitmap::new(10, 10, 0);
+    screen.paste(3, 3, &pic);
+    mock.set_screen(screen);
+    mock.add_pic("a.bmp", pic);
+    let aojia = AoJia::with_backend(mock.clone());
+    let options = WaitOptions::new(Duration::from_secs(1), Duration::from_millis(100))
+        .clock(MockClock::new());
+
+    assert!(matches!(
+        aojia.wait_until_gone(&search(), &options),
+        Err(Error::Timeout(_))
+    ));
+    mock.set_screen(Bitmap::new(10, 10, 0));
+    aojia.wait_until_gone(&search(), &options).unwrap();
+}
+
+#[test]
+fn wait_is_cancelled() {
+    let mock = MockBackend::new();
+    let count = appear_from(&mock, i32::MAX);
+    let token = CancellationToken::new();
+    token.cancel();
+    let options = WaitOptions::default().clock(MockClock::new()).cancel(token);
+
+    let err = AoJia::with_backend(mock).wait_for_pic(&search(), &options);
+    assert!(matches!(err, Err(Error::Cancelled)));
+    assert_eq!(count.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::window::{WindowRelation, WindowState};
+use aojia::{AoJia, Error, MockBackend, Point, Value};
+use common::in_i32;
+
+#[test]
+fn window_state_round_trip() {
+    for flag in 0..=9 {
+        let state = WindowState::try_from(flag).unwrap();
+        assert_eq!(i32::from(state), flag);
+    }
+    assert!(WindowState::try_from(10).is_err());
+    assert_eq!(i32::from(WindowState::TopMost), 8);
+    assert_eq!(i32::from(WindowState::Restore), 5);
+}
+
+/// 布置两个客户端窗口
+#[test]
+fn layout_windows() {
+    let mock = MockBackend::succeeding(&["SetWindowState", "MoveWindow", "SetWindowSize"]);
+    let aojia = AoJia::with_backend(mock.clone());
+    for (i, hwnd) in [100, 200].into_iter().enumerate() {
+        aojia.set_window_state(hwnd, WindowState::Restore).unwrap();
+        aojia
+            .move_window(hwnd, Point::new(i as i32 * 800, 0))
+            .unwrap();
+        aojia.set_window_size(hwnd, 800, 600).unwrap();
+    }
+    let calls: Vec<(String, Vec<RecordedArg>)> =
+        mock.calls().into_iter().map(|c| (c.name, c.args)).collect();
+    let call = |name: &str, args: [i32; 3]| {
+        (
+            name.to_string(),
+            args.iter().map(|&n| RecordedArg::In(n.into())).collect(),
+        )
+    };
+    assert_eq!(calls.len(), 6);
+    assert_eq!(calls[4], call("MoveWindow", [200, 800, 0]));
+    assert_eq!(calls[5], call("SetWindowSize", [200, 800, 600]));
+}
+
+#[test]
+fn failed_operation_is_error() {
+    let mock = MockBackend::new();
+    mock.on("SetWindowTitle", |_| Ok(Value::I32(0)));
+    let aojia = AoJia::with_backend(mock);
+    assert!(matches!(
+        aojia.set_window_title(100, "新标题"),
+        Err(Error::Failed {
+            fun: "SetWindowTitle",
+            ret: 0
+        })
+    ));
+    assert!(matches!(
+        aojia.window_pid(100),
+        Err(Error::Failed {
+            fun: "GetWindowProcessId",
+            ret: 0
+        })
+    ));
+    assert!(matches!(
+        aojia.set_window_size(100, 0, 600),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn related_windows() {
+    let mock = MockBackend::new();
+    mock.on("GetWindow", |args| {
+        Ok(Value::I32(if in_i32(args, 1) == 0 { 0 } else { 300 }))
+    });
+    let aojia = AoJia::with_backend(mock);
+    assert_eq!(aojia.parent_window(100).unwrap(), None);
+    assert_eq!(aojia.child_window(100).unwrap(), Some(300));
+    assert_eq!(
+        aojia.related_window(100, WindowRelation::Owner).unwrap(),
+        Some(300)
+    );
+}
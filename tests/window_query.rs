@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use aojia::backend::mock::RecordedArg;
+use aojia::window::{FindWindowArgs, WindowQuery};
+use aojia::{AoJia, Error, MockBackend, Value};
+
+#[test]
+fn encode_each_flag() {
+    let cases = [
+        (WindowQuery::new().process_name("game.exe"), 1),
+        (WindowQuery::new().pid(1234), 2),
+        (WindowQuery::new().class("Notepad"), 4),
+        (WindowQuery::new().title("无标题"), 8),
+        (WindowQuery::new().title_contains("记事本"), 16),
+        (WindowQuery::new().class("Notepad").visible_only(), 36),
+    ];
+    for (query, ty) in cases {
+        assert_eq!(query.encode().unwrap().ty, ty, "{:?}", query);
+    }
+}
+
+#[test]
+fn encode_combined() {
+    let args = WindowQuery::new()
+        .parent(100)
+        .process_name("game.exe")
+        .pid(1234)
+        .class("Main")
+        .title_contains("游戏")
+        .visible_only()
+        .timeout(Duration::from_millis(1500))
+        .encode()
+        .unwrap();
+    assert_eq!(
+        args,
+        FindWindowArgs {
+            parent: 100,
+            pro_name: "game.exe".to_string(),
+            pro_id: 1234,
+            class: "Main".to_string(),
+            title: "游戏".to_string(),
+            ty: 1 | 2 | 4 | 16 | 32,
+            t: 1500,
+        }
+    );
+}
+
+#[test]
+fn later_title_replaces_earlier() {
+    let args = WindowQuery::new()
+        .title("a")
+        .title_contains("b")
+        .encode()
+        .unwrap();
+    assert_eq!((args.title.as_str(), args.ty), ("b", 16));
+}
+
+#[test]
+fn encode_clamps_timeout() {
+    let args = WindowQuery::new()
+        .pid(1)
+        .timeout(Duration::from_secs(u64::MAX))
+        .encode()
+        .unwrap();
+    assert_eq!(args.t, i32::MAX);
+}
+
+#[test]
+fn encode_requires_condition() {
+    assert!(matches!(
+        WindowQuery::new().encode(),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        WindowQuery::new().visible_only().parent(5).encode(),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn find_window_passes_encoded_args() {
+    let mock = MockBackend::new();
+    mock.on("FindWindow", |_| Ok(Value::I32(0x1234)));
+    let aojia = AoJia::with_backend(mock.clone());
+
+    let query = WindowQuery::new()
+        .class("Notepad")
+        .title("无标题 - 记事本")
+        .timeout(Duration::from_secs(2));
+    assert_eq!(aojia.find_window(&query).unwrap(), Some(0x1234));
+
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].name, "FindWindow");
+    let expected: Vec<Value> = vec![
+        0.into(),
+        "".into(),
+        0.into(),
+        "Notepad".into(),
+        "无标题 - 记事本".into(),
+        12.into(),
+        2000.into(),
+    ];
+    let args: Vec<Value> = calls[0]
+        .args
+        .iter()
+        .map(|arg| match arg {
+            RecordedArg::In(v) => v.clone(),
+            RecordedArg::Out(v) => panic!("FindWindow 不应有返回参数: {:?}", v),
+        })
+        .collect();
+    assert_eq!(args, expected);
+}
+
+#[test]
+fn find_window_not_found() {
+    let mock = MockBackend::new();
+    mock.on("FindWindow", |_| Ok(Value::I32(0)));
+    let aojia = AoJia::with_backend(mock);
+    assert_eq!(aojia.find_window(&WindowQuery::new().pid(1)).unwrap(), None);
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use aojia::window::{WatchOptions, WindowEvent, WindowQuery, WindowSnapshot, WindowWatcher};
+use aojia::{AoJia, MockBackend, MockClock, Point, Value};
+use common::{in_i32, set_out};
+
+#[derive(Debug, Clone)]
+struct Window {
+    title: &'static str,
+    size: (i32, i32),
+    origin: (i32, i32),
+}
+
+type Windows = Arc<Mutex<BTreeMap<i32, Window>>>;
+
+/// 用共享的窗口表模拟 EnumWindow、GetClientSize、ClientToScreen 和 GetWindowTitle
+fn mock_windows(windows: &Windows) -> MockBackend {
+    let mock = MockBackend::new();
+    let w = windows.clone();
+    mock.on("EnumWindow", move |_| {
+        let list: Vec<String> = w.lock().unwrap().keys().map(|h| h.to_string()).collect();
+        Ok(list.join("|").into())
+    });
+    let w = windows.clone();
+    mock.on("GetClientSize", move |args| {
+        let Some(window) = w.lock().unwrap().get(&in_i32(args, 0)).cloned() else {
+            return Ok(Value::I32(0));
+        };
+        set_out(args, 1, window.size.0);
+        set_out(args, 2, window.size.1);
+        Ok(Value::I32(1))
+    });
+    let w = windows.clone();
+    mock.on("ClientToScreen", move |args| {
+        let Some(window) = w.lock().unwrap().get(&in_i32(args, 0)).cloned() else {
+            return Ok(Value::I32(0));
+        };
+        set_out(args, 1, window.origin.0);
+        set_out(args, 2, window.origin.1);
+        Ok(Value::I32(1))
+    });
+    let w = windows.clone();
+    mock.on("GetWindowTitle", move |args| {
+        let title = w
+            .lock()
+            .unwrap()
+            .get(&in_i32(args, 0))
+            .map_or("", |window| window.title);
+        Ok(title.into())
+    });
+    mock
+}
+
+fn window(title: &'static str) -> Window {
+    Window {
+        title,
+        size: (800, 600),
+        origin: (0, 0),
+    }
+}
+
+fn query() -> WindowQuery {
+    WindowQuery::new().class("GameWnd")
+}
+
+#[test]
+fn reports_lifecycle() {
+    let windows = Windows::default();
+    let aojia = AoJia::with_backend(mock_windows(&windows));
+    let mut watcher = WindowWatcher::new(query(), WatchOptions::default());
+    assert_eq!(watcher.poll(&aojia).unwrap(), vec![]);
+
+    windows.lock().unwrap().insert(100, window("客户端"));
+    assert_eq!(
+        watcher.poll(&aojia).unwrap(),
+        vec![WindowEvent::Appeared(WindowSnapshot {
+            hwnd: 100,
+            title: "客户端".to_string(),
+            width: 800,
+            height: 600,
+            origin: Point::new(0, 0),
+        })]
+    );
+    assert_eq!(watcher.poll(&aojia).unwrap(), vec![]);
+
+    {
+        let mut windows = windows.lock().unwrap();
+        let w = windows.get_mut(&100).unwrap();
+        w.size = (1024, 768);
+        w.origin = (50, 60);
+        w.title = "客户端 - 已登录";
+    }
+    assert_eq!(
+        watcher.poll(&aojia).unwrap(),
+        vec![
+            WindowEvent::Resized {
+                hwnd: 100,
+                width: 1024,
+                height: 768
+            },
+            WindowEvent::Moved {
+                hwnd: 100,
+                origin: Point::new(50, 60)
+            },
+            WindowEvent::TitleChanged {
+                hwnd: 100,
+                title: "客户端 - 已登录".to_string()
+            },
+        ]
+    );
+
+    windows.lock().unwrap().remove(&100);
+    assert_eq!(
+        watcher.poll(&aojia).unwrap(),
+        vec![WindowEvent::Closed(100)]
+    );
+    assert_eq!(watcher.poll(&aojia).unwrap(), vec![]);
+}
+
+#[test]
+fn settle_suppresses_intermediate_states() {
+    let windows = Windows::default();
+    windows.lock().unwrap().insert(100, window("客户端"));
+    let aojia = AoJia::with_backend(mock_windows(&windows));
+    let mut watcher = WindowWatcher::new(query(), WatchOptions::default().settle(2));
+    assert_eq!(watcher.poll(&aojia).unwrap(), vec![]);
+    assert_eq!(watcher.poll(&aojia).unwrap().len(), 1);
+
+    // 拖动过程中每次轮询位置都不同，停下后才报告最终位置
+    for x in [10, 20, 30] {
+        windows.lock().unwrap().get_mut(&100).unwrap().origin = (x, 0);
+        assert_eq!(watcher.poll(&aojia).unwrap(), vec![]);
+    }
+    assert_eq!(
+        watcher.poll(&aojia).unwrap(),
+        vec![WindowEvent::Moved {
+            hwnd: 100,
+            origin: Point::new(30, 0)
+        }]
+    );
+
+    // 一闪而过的窗口不报告
+    windows.lock().unwrap().insert(200, window("弹窗"));
+    assert_eq!(watcher.poll(&aojia).unwrap(), vec![]);
+    windows.lock().unwrap().remove(&200);
+    assert_eq!(watcher.poll(&aojia).unwrap(), vec![]);
+    assert_eq!(watcher.poll(&aojia).unwrap(), vec![]);
+}
+
+#[test]
+fn background_watcher_sends_events() {
+    let windows = Windows::default();
+    let mock = mock_windows(&windows);
+    let options = WatchOptions::new(Duration::from_millis(10)).clock(MockClock::new());
+    let handle = WindowWatcher::new(query(), options).spawn(move || Ok(AoJia::with_backend(mock)));
+
+    windows.lock().unwrap().insert(100, window("客户端"));
+    let recv = || {
+        handle
+            .events()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .unwrap()
+    };
+    assert!(matches!(recv(), WindowEvent::Appeared(s) if s.hwnd == 100));
+    windows.lock().unwrap().remove(&100);
+    assert_eq!(recv(), WindowEvent::Closed(100));
+    handle.stop();
+}